@@ -1,11 +1,14 @@
-use ariadne_next::{Ansi, Color, Label, Report, ReportKind, SourceView, StyleExt};
+use ariadne_next::{
+    ColorChoice, ColorGenerator, Label, Report, ReportKind, SourceView, Stream, StyleExt,
+};
 
 fn main() {
-    let mut backend = Ansi(std::io::stdout().lock());
+    let mut backend = Stream::stdout(ColorChoice::Auto);
 
-    let a = Color::Cyan;
-    let b = Color::Green;
-    let c = Color::Yellow;
+    let mut colors = ColorGenerator::new();
+    let a = colors.next().unwrap();
+    let b = colors.next().unwrap();
+    let c = colors.next().unwrap();
 
     Report::new(ReportKind::Error)
         .with_code(3)
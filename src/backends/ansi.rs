@@ -1,10 +1,63 @@
-use super::{layout_report, Render};
-use crate::{Cache, Report};
+use super::{layout_explanation, layout_report, Render};
+use crate::{Cache, Color, ColorDepth, Explanation, Report, Underline};
+use std::fmt::Write;
 use std::io;
 
+/// Writes reports with ANSI escape sequences, colors are written as they are
+///
+/// [`Stream`](crate::Stream) detects which colors a terminal supports and can write hyperlinks.
 pub struct Ansi<W: io::Write>(pub W);
 pub type AnsiError = io::Error;
 
+#[derive(Clone, Copy)]
+/// How styles and links are written, the [`Ansi`] backend writes all colors and no links
+pub(super) struct AnsiOptions {
+    /// Colors that can not be displayed are replaced with the nearest supported color
+    pub(super) color_depth: ColorDepth,
+    /// Writes OSC 8 hyperlinks for source names and codes, see [`Cache::link`] and [`Report::with_url`]
+    pub(super) hyperlinks: bool,
+}
+
+impl Default for AnsiOptions {
+    fn default() -> Self {
+        Self {
+            color_depth: ColorDepth::TrueColor,
+            hyperlinks: false,
+        }
+    }
+}
+
+impl<W: io::Write> Ansi<W> {
+    pub fn new(writer: W) -> Self {
+        Self(writer)
+    }
+
+    pub(super) fn write_with_options<SourceId>(
+        &mut self,
+        report: &Report<SourceId>,
+        cache: &mut impl Cache<SourceId>,
+        options: AnsiOptions,
+    ) -> Result<(), AnsiError> {
+        let element = layout_report(report, cache);
+        for line in options.render(&element) {
+            writeln!(self.0, "{line}")?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn write_explanation_with_options(
+        &mut self,
+        explanation: &Explanation,
+        options: AnsiOptions,
+    ) -> Result<(), AnsiError> {
+        let element = layout_explanation(explanation);
+        for line in options.render(&element) {
+            writeln!(self.0, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
 impl<W: io::Write> crate::Backend for Ansi<W> {
     type Error = AnsiError;
 
@@ -13,34 +66,240 @@ impl<W: io::Write> crate::Backend for Ansi<W> {
         report: &Report<SourceId>,
         cache: &mut impl Cache<SourceId>,
     ) -> Result<(), Self::Error> {
-        let element = layout_report(report, cache);
-        Self::render(&mut self.0, &element)
+        self.write_with_options(report, cache, AnsiOptions::default())
+    }
+
+    fn write_explanation(&mut self, explanation: &Explanation) -> Result<(), Self::Error> {
+        self.write_explanation_with_options(explanation, AnsiOptions::default())
     }
 }
 
-impl<W: io::Write> Render for Ansi<W> {
-    fn write_style_prefix(string: &mut String, style: &crate::Style) {
-        yansi::Style::from(style).fmt_prefix(string).unwrap();
+impl Render for AnsiOptions {
+    fn write_style_prefix(&self, string: &mut String, style: &crate::Style) {
+        let style = self.color_depth.downgrade_style(*style);
+
+        let params = sgr_params(&style);
+        if !params.is_empty() {
+            write!(string, "\x1B[{}m", params.join(";")).unwrap();
+        }
+        write_extended_prefix(string, &style);
     }
 
-    fn write_style_suffix(string: &mut String, style: &crate::Style) {
-        yansi::Style::from(style).fmt_suffix(string).unwrap();
+    fn write_style_suffix(&self, string: &mut String, style: &crate::Style) {
+        let style = self.color_depth.downgrade_style(*style);
+        if style != crate::Style::default() {
+            string.push_str("\x1B[0m");
+        }
     }
-}
 
-impl From<&crate::Style> for yansi::Style {
-    fn from(value: &crate::Style) -> Self {
-        let mut style = yansi::Style::default()
-            .fg(value.foreground)
-            .bg(value.background);
+    fn write_link_prefix(&self, string: &mut String, target: &str) {
+        if self.hyperlinks {
+            write!(string, "\x1B]8;;{target}\x1B\\").unwrap();
+        }
+    }
 
-        if value.is_bold() {
-            style = style.bold();
+    fn write_link_suffix(&self, string: &mut String, _target: &str) {
+        if self.hyperlinks {
+            string.push_str("\x1B]8;;\x1B\\");
         }
-        if value.is_italic() {
-            style = style.italic();
+    }
+}
+
+/// Parameters of the SGR sequence for the attributes and colors of the style
+fn sgr_params(style: &crate::Style) -> Vec<String> {
+    let attributes = [
+        (style.is_bold(), "1"),
+        (style.is_dim(), "2"),
+        (style.is_italic(), "3"),
+        (style.is_underline(), "4"),
+        (style.is_blink(), "5"),
+        (style.is_reverse(), "7"),
+        (style.is_hidden(), "8"),
+        (style.is_strikethrough(), "9"),
+    ];
+    let mut params = attributes
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, param)| param.to_string())
+        .collect::<Vec<_>>();
+
+    params.extend(color_param(style.background, 40));
+    params.extend(color_param(style.foreground, 30));
+    params
+}
+
+/// `base` is 30 for the foreground and 40 for the background
+fn color_param(color: Color, base: u8) -> Option<String> {
+    match color {
+        Color::Unset => None,
+        Color::Default => Some(format!("{}", base + 9)),
+        Color::Fixed(index) => Some(format!("{};5;{index}", base + 8)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{r};{g};{b}", base + 8)),
+        named => {
+            let index = named.named_index().unwrap();
+            match index {
+                0..=7 => Some(format!("{}", base + index)),
+                // Bright colors use 90-97 and 100-107
+                _ => Some(format!("{}", base + 60 + index - 8)),
+            }
         }
+    }
+}
+
+/// Writes the SGR sequences for attributes that are not as widely supported
+fn write_extended_prefix(string: &mut String, style: &crate::Style) {
+    if style.is_overline() {
+        string.push_str("\x1B[53m");
+    }
 
-        style
+    let kind = match style.underline {
+        None | Some(Underline::Single) => None,
+        Some(Underline::Double) => Some(2),
+        Some(Underline::Curly) => Some(3),
+        Some(Underline::Dotted) => Some(4),
+        Some(Underline::Dashed) => Some(5),
+    };
+    if let Some(kind) = kind {
+        write!(string, "\x1B[4:{kind}m").unwrap();
     }
+
+    match style.underline_color {
+        Color::Unset => {}
+        Color::Default => string.push_str("\x1B[59m"),
+        Color::Fixed(index) => write!(string, "\x1B[58;5;{index}m").unwrap(),
+        Color::Rgb(r, g, b) => write!(string, "\x1B[58;2;{r};{g};{b}m").unwrap(),
+        named => write!(string, "\x1B[58;5;{}m", named.named_index().unwrap()).unwrap(),
+    }
+}
+
+#[test]
+fn test_style_sequences() {
+    use crate::Style;
+
+    let sequences = |style: Style| {
+        let mut prefix = String::new();
+        let mut suffix = String::new();
+        let backend = AnsiOptions::default();
+        backend.write_style_prefix(&mut prefix, &style);
+        backend.write_style_suffix(&mut suffix, &style);
+        (prefix, suffix)
+    };
+
+    assert_eq!(sequences(Style::new()), ("".into(), "".into()));
+    assert_eq!(
+        sequences(
+            Style::new()
+                .bold()
+                .dim()
+                .reverse()
+                .strikethrough()
+                .fg(Color::Red)
+        ),
+        ("\x1B[1;2;7;9;31m".into(), "\x1B[0m".into())
+    );
+    assert_eq!(
+        sequences(Style::new().overline()),
+        ("\x1B[53m".into(), "\x1B[0m".into())
+    );
+    assert_eq!(
+        sequences(
+            Style::new()
+                .underline_kind(Underline::Curly)
+                .underline_color(Color::Rgb(255, 128, 0))
+        ),
+        (
+            "\x1B[4m\x1B[4:3m\x1B[58;2;255;128;0m".into(),
+            "\x1B[0m".into()
+        )
+    );
+}
+
+#[test]
+fn test_nested_styles() {
+    use crate::{tree::Element, Style};
+
+    let element = Element::hstack([
+        Element::inline("This is of type ").styled(Style::default()),
+        Element::inline("Nat").styled(Style::new().fg(Color::Cyan)),
+        Element::inline(" and ").styled(Style::default()),
+        Element::inline("Str").styled(Style::new().fg(Color::Green).italic()),
+    ])
+    .styled(Style::new().bold().fg(Color::Red));
+
+    // The text after each child is still bold and red
+    assert_eq!(
+        AnsiOptions::default().render(&element),
+        ["\x1B[1;31mThis is of type \x1B[0m\x1B[1;36mNat\x1B[0m\x1B[1;31m and \x1B[0m\x1B[1;3;32mStr\x1B[0m"]
+    );
+}
+
+#[test]
+fn test_color_depth() {
+    use crate::Style;
+
+    let style = Style::new()
+        .fg(Color::Rgb(255, 135, 0))
+        .bg(Color::Fixed(21))
+        .bold();
+    let prefix = |color_depth| {
+        let mut prefix = String::new();
+        let options = AnsiOptions {
+            color_depth,
+            hyperlinks: false,
+        };
+        options.write_style_prefix(&mut prefix, &style);
+        prefix
+    };
+
+    assert_eq!(
+        prefix(ColorDepth::TrueColor),
+        "\x1B[1;48;5;21;38;2;255;135;0m"
+    );
+    assert_eq!(prefix(ColorDepth::Ansi256), "\x1B[1;48;5;21;38;5;208m");
+    assert_eq!(prefix(ColorDepth::Ansi16), "\x1B[1;44;33m");
+    assert_eq!(prefix(ColorDepth::None), "\x1B[1m");
+}
+
+#[test]
+fn test_color_sequences() {
+    use crate::Style;
+
+    let prefix = |style: Style| {
+        let mut prefix = String::new();
+        AnsiOptions::default().write_style_prefix(&mut prefix, &style);
+        prefix
+    };
+
+    assert_eq!(prefix(Style::new().fg(Color::Red)), "\x1B[31m");
+    assert_eq!(prefix(Style::new().fg(Color::BrightRed)), "\x1B[91m");
+    assert_eq!(prefix(Style::new().bg(Color::BrightWhite)), "\x1B[107m");
+    assert_eq!(prefix(Style::new().fg(Color::Default)), "\x1B[39m");
+    assert_eq!(
+        prefix(Style::new().underline_color(Color::BrightBlue)),
+        "\x1B[58;5;12m"
+    );
+}
+
+#[test]
+fn test_hyperlinks() {
+    use crate::{tree::Element, Style};
+
+    let element = Element::hstack([
+        Element::link("[E0412]", "https://example.com/E0412").styled(Style::new().fg(Color::Red)),
+        Element::inline(" text").styled(Style::default()),
+    ])
+    .styled(Style::default());
+
+    assert_eq!(
+        AnsiOptions::default().render(&element),
+        ["\x1B[31m[E0412]\x1B[0m text"]
+    );
+    let options = AnsiOptions {
+        hyperlinks: true,
+        ..AnsiOptions::default()
+    };
+    assert_eq!(
+        options.render(&element),
+        ["\x1B]8;;https://example.com/E0412\x1B\\\x1B[31m[E0412]\x1B[0m\x1B]8;;\x1B\\ text"]
+    );
 }
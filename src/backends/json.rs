@@ -0,0 +1,350 @@
+use super::{is_primary, lines_cols, lines_enclosing_spans, lines_with_offsets, plain_text};
+use crate::{
+    Ansi, Backend, Cache, Catalog, DiagnosticDef, Explanation, Locale, PlainText, Report,
+    ReportKind, SourceView,
+};
+use crate::{Span, StyledStr};
+use std::{fmt, io};
+
+/// Writes each report as a single line of JSON, in the format of rustc's `--error-format=json`
+///
+/// Every label becomes a span, labels that contain the location of their [`SourceView`] are primary.
+/// If none of them does, the first label is primary.
+/// Columns count characters starting at 1, column and highlight ends are exclusive.
+/// Views whose source can not be fetched have no spans.
+/// Comments become children without spans, code explanations are taken from [`Json::with_catalog`].
+pub struct Json<'a, W: io::Write>(pub W, JsonOptions<'a>);
+pub type JsonError = io::Error;
+
+struct JsonOptions<'a> {
+    rendered: Rendered,
+    catalog: Option<&'a Catalog>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Rendering of the report in the `rendered` field of [`Json`] output
+pub enum Rendered {
+    /// The field is `null`
+    #[default]
+    None,
+    /// Rendered like the [`PlainText`] backend
+    PlainText,
+    /// Rendered like the [`Ansi`] backend
+    Ansi,
+}
+
+impl<'a, W: io::Write> Json<'a, W> {
+    pub fn new(writer: W) -> Self {
+        Self(
+            writer,
+            JsonOptions {
+                rendered: Rendered::default(),
+                catalog: None,
+            },
+        )
+    }
+
+    pub fn with_rendered(mut self, rendered: Rendered) -> Self {
+        self.1.rendered = rendered;
+        self
+    }
+
+    pub fn set_rendered(&mut self, rendered: Rendered) {
+        self.1.rendered = rendered;
+    }
+
+    /// Fills the `explanation` of codes that have one in the catalog, they are `null` otherwise
+    pub fn with_catalog(mut self, catalog: &'a Catalog) -> Self {
+        self.1.catalog = Some(catalog);
+        self
+    }
+
+    pub fn set_catalog(&mut self, catalog: &'a Catalog) {
+        self.1.catalog = Some(catalog);
+    }
+}
+
+impl<W: io::Write> Backend for Json<'_, W> {
+    type Error = JsonError;
+
+    fn write<SourceId>(
+        &mut self,
+        report: &Report<SourceId>,
+        cache: &mut impl Cache<SourceId>,
+    ) -> Result<(), Self::Error> {
+        let rendered = match self.1.rendered {
+            Rendered::None => Value::Null,
+            Rendered::PlainText => {
+                let mut backend = PlainText(Vec::new());
+                report.write(&mut backend, &mut *cache)?;
+                String::from_utf8_lossy(&backend.0).into()
+            }
+            Rendered::Ansi => {
+                let mut backend = Ansi(Vec::new());
+                report.write(&mut backend, &mut *cache)?;
+                String::from_utf8_lossy(&backend.0).into()
+            }
+        };
+
+        let children = report
+            .comments
+            .iter()
+            .map(|(kind, message)| diagnostic(message, None, *kind, vec![], vec![], Value::Null))
+            .collect();
+        let spans = report
+            .views
+            .iter()
+            .flat_map(|view| spans(view, cache, report.locale))
+            .collect();
+
+        let code = report.code.as_deref().map(|code| {
+            let explanation = (self.1.catalog)
+                .and_then(|catalog| catalog.get(code))
+                .and_then(DiagnosticDef::explanation);
+            Value::Object(vec![
+                ("code", code.into()),
+                ("explanation", explanation.into()),
+            ])
+        });
+        let mut value = diagnostic(
+            &report.message,
+            code,
+            report.kind,
+            spans,
+            children,
+            rendered,
+        );
+        if let Value::Object(fields) = &mut value {
+            fields.insert(0, ("$message_type", "diagnostic".into()));
+        }
+        writeln!(self.0, "{value}")
+    }
+
+    /// Writes an object with the `code` and the `explanation` text
+    fn write_explanation(&mut self, explanation: &Explanation) -> Result<(), Self::Error> {
+        let value = Value::Object(vec![
+            ("$message_type", "explanation".into()),
+            ("code", explanation.code.into()),
+            ("explanation", explanation.text.into()),
+        ]);
+        writeln!(self.0, "{value}")
+    }
+}
+
+fn diagnostic(
+    message: &[StyledStr],
+    code: Option<Value>,
+    kind: ReportKind,
+    spans: Vec<Value>,
+    children: Vec<Value>,
+    rendered: Value,
+) -> Value {
+    Value::Object(vec![
+        ("message", plain_text(message).into()),
+        ("code", code.unwrap_or(Value::Null)),
+        ("level", level(kind).into()),
+        ("spans", Value::Array(spans)),
+        ("children", Value::Array(children)),
+        ("rendered", rendered),
+    ])
+}
+
+/// Level names of rustc, custom kinds use their lowercase name
+fn level(kind: ReportKind) -> String {
+    match kind {
+        ReportKind::Error => "error".into(),
+        ReportKind::Warning => "warning".into(),
+        ReportKind::Help => "help".into(),
+        ReportKind::Note => "note".into(),
+        ReportKind::Custom(name, _) => name.to_lowercase(),
+    }
+}
+
+fn spans<SourceId>(
+    view: &SourceView<SourceId>,
+    cache: &mut impl Cache<SourceId>,
+    locale: &dyn Locale,
+) -> Vec<Value> {
+    let file_name = match cache.display_id(&view.source_id) {
+        Some(id) => id.to_string(),
+        None => locale.unknown_source().into_owned(),
+    };
+    let Ok(source) = cache.fetch(&view.source_id) else {
+        return vec![];
+    };
+
+    if view.labels.is_empty() {
+        let location = view.location..view.location;
+        return span(source, &file_name, &location, None, true)
+            .into_iter()
+            .collect();
+    }
+    (view.labels.iter().enumerate())
+        .filter_map(|(index, label)| {
+            let message = label.message.as_deref();
+            span(
+                source,
+                &file_name,
+                &label.span,
+                message,
+                is_primary(view, index),
+            )
+        })
+        .collect()
+}
+
+/// `None` if the span is not inside of the source
+fn span(
+    source: &str,
+    file_name: &str,
+    span: &Span,
+    label: Option<&[StyledStr]>,
+    is_primary: bool,
+) -> Option<Value> {
+    source.get(span.clone())?;
+    let (line_start, column_start) = lines_cols(source, span.start, 1);
+    let (line_end, column_end) = lines_cols(source, span.end, 1);
+
+    let block = lines_enclosing_spans(source, std::iter::once(span));
+    let text = lines_with_offsets(&source[block.clone()], block.start)
+        .map(|(offset, line)| {
+            let highlight_start = match offset <= span.start {
+                true => column_start,
+                false => 1,
+            };
+            let highlight_end = match span.end <= offset + line.len() {
+                true => column_end,
+                false => line.chars().count() + 1,
+            };
+            Value::Object(vec![
+                ("text", line.into()),
+                ("highlight_start", highlight_start.into()),
+                ("highlight_end", highlight_end.into()),
+            ])
+        })
+        .collect();
+
+    Some(Value::Object(vec![
+        ("file_name", file_name.into()),
+        ("byte_start", span.start.into()),
+        ("byte_end", span.end.into()),
+        ("line_start", line_start.into()),
+        ("line_end", line_end.into()),
+        ("column_start", column_start.into()),
+        ("column_end", column_end.into()),
+        ("is_primary", is_primary.into()),
+        ("text", Value::Array(text)),
+        ("label", label.map(plain_text).into()),
+        ("suggested_replacement", Value::Null),
+        ("suggestion_applicability", Value::Null),
+        ("expansion", Value::Null),
+    ]))
+}
+
+/// JSON document, written without whitespace by [`Display`](fmt::Display)
+pub(super) enum Value {
+    Null,
+    Bool(bool),
+    Number(usize),
+    String(String),
+    Array(Vec<Value>),
+    /// Fields in the order they are written
+    Object(Vec<(&'static str, Value)>),
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Value::Bool(value)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Self {
+        Value::Number(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value)
+    }
+}
+
+impl From<std::borrow::Cow<'_, str>> for Value {
+    fn from(value: std::borrow::Cow<'_, str>) -> Self {
+        Value::String(value.into_owned())
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => f.write_str("null"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Number(value) => write!(f, "{value}"),
+            Value::String(value) => write_string(f, value),
+            Value::Array(values) => {
+                f.write_str("[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                f.write_str("]")
+            }
+            Value::Object(fields) => {
+                f.write_str("{")?;
+                for (index, (name, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, string: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in string.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+#[test]
+fn test_value() {
+    let value = Value::Object(vec![
+        ("text", "\"quoted\"\n\x1B[1m".into()),
+        ("numbers", Value::Array(vec![1.into(), 22.into()])),
+        ("empty", None::<bool>.into()),
+    ]);
+    assert_eq!(
+        value.to_string(),
+        r#"{"text":"\"quoted\"\n\u001b[1m","numbers":[1,22],"empty":null}"#
+    );
+}
@@ -0,0 +1,178 @@
+use super::{
+    invisible_chars_note, lines_cols, lines_enclosing_spans, lines_with_offsets, plain_text,
+    sanitize,
+};
+use crate::catalog::Block;
+use crate::{Cache, Config, Explanation, Label, Locale, Report, ReportKind, SourceView};
+use std::io;
+
+/// Writes reports as linear prose without decorative characters or alignment,
+/// so they can be read out by screen readers.
+///
+/// Styles are ignored, each sentence is written on its own line.
+pub struct Linear<W: io::Write>(pub W);
+pub type LinearError = io::Error;
+
+impl<W: io::Write> Linear<W> {
+    pub fn new(writer: W) -> Self {
+        Self(writer)
+    }
+}
+
+impl<W: io::Write> crate::Backend for Linear<W> {
+    type Error = LinearError;
+
+    fn write<SourceId>(
+        &mut self,
+        report: &Report<SourceId>,
+        cache: &mut impl Cache<SourceId>,
+    ) -> Result<(), Self::Error> {
+        for line in layout_report(report, cache) {
+            writeln!(self.0, "{line}")?;
+        }
+        Ok(())
+    }
+
+    fn write_explanation(&mut self, explanation: &Explanation) -> Result<(), Self::Error> {
+        for line in layout_explanation(explanation) {
+            writeln!(self.0, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+fn layout_report<SourceId>(
+    report: &Report<SourceId>,
+    cache: &mut impl Cache<SourceId>,
+) -> Vec<String> {
+    let mut lines = vec![];
+
+    let locale = report.locale;
+    let kind = locale.kind_name(report.kind);
+    let message = plain_text(&report.message);
+    lines.push(match &report.code {
+        Some(code) => format!("{kind} {code}: {message}"),
+        None => format!("{kind}: {message}"),
+    });
+
+    for view in &report.views {
+        layout_source(&mut lines, view, cache, locale, &report.config);
+    }
+
+    if let Some(note) = invisible_chars_note(report, cache) {
+        lines.push(format!("{}: {note}", locale.kind_name(ReportKind::Note)));
+    }
+
+    for (kind, message) in &report.comments {
+        lines.push(format!(
+            "{}: {}",
+            locale.kind_name(*kind),
+            plain_text(message)
+        ));
+    }
+
+    lines
+}
+
+fn layout_source<SourceId>(
+    lines: &mut Vec<String>,
+    view: &SourceView<SourceId>,
+    cache: &mut impl Cache<SourceId>,
+    locale: &dyn Locale,
+    config: &Config,
+) {
+    let name = cache.display_id(&view.source_id).map(|id| id.to_string());
+
+    let Ok(source) = cache.fetch(&view.source_id) else {
+        return lines.push(locale.linear_location(name.as_deref(), None));
+    };
+
+    let position = lines_cols(source, view.location, config.tab_width);
+    lines.push(locale.linear_location(name.as_deref(), Some(position)));
+
+    let block = lines_enclosing_spans(source, view.labels.iter().map(|Label { span, .. }| span));
+    for (offset, text) in lines_with_offsets(&source[block.clone()], block.start) {
+        let (line, _) = lines_cols(source, offset, config.tab_width);
+        let text = sanitize(text.trim(), config.tab_width);
+        lines.push(locale.linear_source_line(line, &text));
+    }
+
+    for label in &view.labels {
+        let start = lines_cols(source, label.span.start, config.tab_width);
+        // The end column is the column of the last labeled character
+        let last = source[label.span.start..label.span.end]
+            .char_indices()
+            .next_back()
+            .map_or(label.span.start, |(index, _)| label.span.start + index);
+        let end = lines_cols(source, last, config.tab_width);
+
+        let message = label.message.as_deref().map(plain_text);
+        lines.push(locale.linear_label(start, end, message.as_deref()));
+    }
+}
+
+fn layout_explanation(explanation: &Explanation) -> Vec<String> {
+    let tab_width = explanation.config.tab_width;
+    let mut lines = vec![];
+    for block in explanation.blocks() {
+        match block {
+            Block::Heading(text) | Block::Text(text) => {
+                let text = sanitize(text.trim(), tab_width);
+                // Empty lines only separate paragraphs
+                if !text.is_empty() {
+                    lines.push(text.into_owned());
+                }
+            }
+            Block::Code(code) => {
+                lines.push(explanation.locale.linear_code_example(code.len()));
+                for (index, line) in code.iter().enumerate() {
+                    let text = sanitize(line.trim(), tab_width);
+                    lines.push(explanation.locale.linear_source_line(index + 1, &text));
+                }
+            }
+        }
+    }
+    lines
+}
+
+#[test]
+fn test_multibyte_label_end() {
+    use crate::Backend;
+
+    let source = "let größe = 1;\n";
+    // The label ends in `ß`, which is two bytes long
+    let report = Report::new(ReportKind::Warning)
+        .with_message("unused variable")
+        .with_view(SourceView::new("main.rs", 4).with_label(Label::new(4..10)));
+
+    let mut backend = Linear(Vec::new());
+    backend
+        .write(&report, &mut vec![("main.rs", source)])
+        .unwrap();
+    let output = String::from_utf8(backend.0).unwrap();
+    assert!(
+        output.contains("Label on line 1 from column 5 to 8."),
+        "{output}"
+    );
+}
+
+#[test]
+fn test_invisible_chars_note() {
+    use crate::Backend;
+
+    let source = "let s = \"a\u{202e}b\";\n";
+    let report = Report::new(ReportKind::Warning)
+        .with_message("suspicious string literal")
+        .with_config(Config::new().with_invisible_chars_note(true))
+        .with_view(SourceView::new("main.rs", 8).with_label(Label::new(8..15)));
+
+    let mut backend = Linear(Vec::new());
+    backend
+        .write(&report, &mut vec![("main.rs", source)])
+        .unwrap();
+    let output = String::from_utf8(backend.0).unwrap();
+    assert!(
+        output.contains("Note: labeled source code contains invisible characters (U+202E)"),
+        "{output}"
+    );
+}
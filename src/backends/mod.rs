@@ -1,34 +1,82 @@
-use crate::style::{Style, Styled, StyledStr};
+use crate::style::{Style, Styled, StyledStr, StyledText};
 use crate::tree::Element;
-use crate::{Cache, Label, Report, ReportKind, SourceView, Span};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::catalog::{Block, Explanation};
+use crate::{
+    Cache, Color, Config, Highlighter, Label, Locale, Report, ReportKind, SourceView, Span, Theme,
+};
 
 mod render;
 use render::Render;
 
+mod sanitize;
+use sanitize::{is_invisible, sanitize};
+
 mod plaintext;
 pub use plaintext::PlainText;
 
 mod ansi;
 pub use ansi::Ansi;
 
+mod stream;
+pub use stream::Stream;
+
+mod linear;
+pub use linear::Linear;
+
+mod json;
+pub use json::{Json, Rendered};
+
+mod sarif;
+pub use sarif::Sarif;
+
 fn layout_report<SourceId>(
     report: &Report<SourceId>,
     cache: &mut impl Cache<SourceId>,
 ) -> Styled<Element> {
     let mut vstack: Vec<Styled<Element>> = vec![];
 
+    let theme = &report.config.theme;
     vstack.push(layout_message(
         report.kind,
-        report.code.as_ref(),
+        report.code.as_deref(),
+        report.url.as_deref(),
         &report.message,
+        theme,
+        report.locale,
     ));
 
     for view in &report.views {
-        vstack.push(layout_source(view, cache));
+        vstack.push(layout_source(
+            view,
+            cache,
+            report.highlighter,
+            report.locale,
+            &report.config,
+        ));
+    }
+
+    if let Some(note) = invisible_chars_note(report, cache) {
+        vstack.push(layout_message(
+            ReportKind::Note,
+            None,
+            None,
+            &note.parts_vec(),
+            theme,
+            report.locale,
+        ));
     }
 
     for (kind, message) in &report.comments {
-        vstack.push(layout_message(*kind, None, message));
+        vstack.push(layout_message(
+            *kind,
+            None,
+            None,
+            message,
+            theme,
+            report.locale,
+        ));
     }
 
     Element::vstack(vstack).styled(Style::default())
@@ -36,17 +84,25 @@ fn layout_report<SourceId>(
 
 fn layout_message(
     kind: ReportKind,
-    code: Option<&String>,
+    code: Option<&str>,
+    url: Option<&str>,
     message: &[StyledStr<'_>],
+    theme: &Theme,
+    locale: &dyn Locale,
 ) -> Styled<Element> {
     let mut hstack: Vec<Styled<Element>> = vec![];
 
-    let kind = kind.styled().map(Element::inline);
+    let kind = kind.styled(theme, locale).map(Element::inline);
 
-    let kind_style = kind.style().clone();
-    if let Some(code) = &code {
+    let kind_style = *kind.style();
+    match (code, url) {
+        (Some(code), Some(url)) => {
+            hstack.push(Element::link(format!("[{code}]"), url).styled(kind_style));
+            hstack.push(Element::inline(" ").styled(kind_style));
+        }
         // TODO Subobptimal should be combined with kind element
-        hstack.push(Element::inline(format!("[{code}] ")).styled(kind_style));
+        (Some(code), None) => hstack.push(Element::inline(format!("[{code}] ")).styled(kind_style)),
+        (None, _) => {}
     }
     hstack.push(kind);
 
@@ -64,29 +120,52 @@ fn layout_message(
 fn layout_source<SourceId>(
     view: &SourceView<SourceId>,
     cache: &mut impl Cache<SourceId>,
+    highlighter: Option<&dyn Highlighter<SourceId>>,
+    locale: &dyn Locale,
+    config: &Config,
 ) -> Styled<Element> {
     let mut vstack = vec![];
 
     let name = cache
         .display_id(&view.source_id)
         .map(|id| id.to_string())
-        .unwrap_or("<unkown>".into());
+        .unwrap_or(locale.unknown_source().into_owned());
 
-    let source = cache.fetch(&view.source_id).unwrap();
+    // Sources that can not be fetched only get a header with their name and the labels
+    let mut position = None;
+    if let Ok(source) = cache.fetch(&view.source_id) {
+        position = Some(lines_cols(source, view.location, config.tab_width));
 
-    let (lines, cols) = lines_cols(source, view.location, 4);
-    vstack.push(Element::inline(format!("[{name}:{lines}:{cols}]")).styled(Style::default()));
+        vstack.push(Element::inline("").styled(Style::default()));
 
-    vstack.push(Element::inline("").styled(Style::default()));
+        let labels = label_colors(view);
+        let block =
+            lines_enclosing_spans(source, view.labels.iter().map(|Label { span, .. }| span));
+        vstack.extend(lines_with_offsets(&source[block.clone()], block.start).map(
+            |(offset, line)| {
+                let highlights = highlighter
+                    .map(|highlighter| highlighter.highlight(&view.source_id, line))
+                    .unwrap_or_default();
+                layout_line(line, offset, &highlights, &labels, config)
+            },
+        ));
 
-    let block = lines_enclosing_spans(source, view.labels.iter().map(|Label { span, .. }| span));
-    vstack.extend(
-        block
-            .lines()
-            .map(|line| Element::inline(line).styled(Style::default())),
-    );
+        vstack.push(Element::inline("").styled(Style::default()));
+    }
 
-    vstack.push(Element::inline("").styled(Style::default()));
+    // Created after the lines, as the cache can not be borrowed while the source is
+    let header = match position {
+        Some((lines, cols)) => match cache.link(&view.source_id, lines, cols) {
+            Some(target) => Element::hstack([
+                Element::inline("[").styled(Style::default()),
+                Element::link(format!("{name}:{lines}:{cols}"), target).styled(Style::default()),
+                Element::inline("]").styled(Style::default()),
+            ]),
+            None => Element::inline(format!("[{name}:{lines}:{cols}]")),
+        },
+        None => Element::inline(format!("[{name}]")),
+    };
+    vstack.insert(0, header.styled(Style::default()));
 
     // TODO How to build Elements for labels?
     for label in &view.labels {
@@ -103,14 +182,20 @@ fn layout_source<SourceId>(
                         )
                         .styled(Style::default())
                     })
-                    .unwrap_or(Element::inline("<empty label>").styled(Style::default())),
+                    .unwrap_or(Element::inline(locale.empty_label()).styled(Style::default())),
                 Element::inline(format!(" {:?}", label.span)).styled(Style::default()),
             ])
             .styled(Style::default()),
         )
     }
 
-    // TODO Do not use hardcoded characters
+    let Theme {
+        hbar,
+        vbar,
+        ltop,
+        rbot,
+        ..
+    } = config.theme;
     let border = Element::vstack(
         vstack
             .iter()
@@ -119,11 +204,11 @@ fn layout_source<SourceId>(
             .chain(Some(vstack.len()))
             .map(|i| {
                 Element::inline(match i {
-                    0 => "   ╭─",
-                    _ if i == vstack.len() => "───╯ ",
-                    _ => "   │ ",
+                    0 => format!("   {ltop}{hbar}"),
+                    _ if i == vstack.len() => format!("{hbar}{hbar}{hbar}{rbot} "),
+                    _ => format!("   {vbar} "),
                 })
-                .styled(Style::default())
+                .styled(config.theme.border)
             }),
     );
 
@@ -134,38 +219,229 @@ fn layout_source<SourceId>(
     .styled(Style::default())
 }
 
-fn lines_cols(source: &str, location: usize, tab_width: u32) -> (usize, u32) {
+fn layout_explanation(explanation: &Explanation) -> Styled<Element> {
+    let config = &explanation.config;
+    let vstack = explanation.blocks().into_iter().map(|block| match block {
+        Block::Heading(text) => {
+            Element::inline(sanitize(text, config.tab_width)).styled(Style::new().bold())
+        }
+        Block::Text(text) => {
+            Element::inline(sanitize(text, config.tab_width)).styled(Style::default())
+        }
+        Block::Code(lines) => {
+            // Code examples get the same border as source code, without the corners
+            let border = Element::vstack(lines.iter().map(|_| {
+                Element::inline(format!("   {} ", config.theme.vbar)).styled(config.theme.border)
+            }));
+            let lines = Element::vstack(lines.iter().map(|line| {
+                Element::inline(sanitize(line, config.tab_width)).styled(Style::default())
+            }));
+            Element::hstack([
+                border.styled(Style::default()),
+                lines.styled(Style::default()),
+            ])
+            .styled(Style::default())
+        }
+    });
+    Element::vstack(vstack).styled(Style::default())
+}
+
+/// Labels that contain the location of their view are primary, or the first label if none does
+fn is_primary<SourceId>(view: &SourceView<SourceId>, index: usize) -> bool {
+    let contains_location =
+        |label: &Label| label.span.contains(&view.location) || label.span.start == view.location;
+    match view.labels.iter().any(contains_location) {
+        true => contains_location(&view.labels[index]),
+        false => index == 0,
+    }
+}
+
+/// Line and column of the location, both starting at 1. Columns count characters,
+/// except for tabs which count as `tab_width` columns.
+fn lines_cols(source: &str, location: usize, tab_width: usize) -> (usize, usize) {
     let source_before = &source[..location];
-    let lines = source_before.lines().count();
-    let cols = source_before
-        .lines()
-        .last()
-        .map(|line| {
-            1 + line
-                .chars()
-                .map(|c| match c {
-                    '\t' => tab_width,
-                    _ => 1,
-                })
-                .sum::<u32>()
+    let lines = 1 + source_before.matches('\n').count();
+    let line_start = source_before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let cols = 1 + source_before[line_start..]
+        .chars()
+        .map(|c| match c {
+            '\t' => tab_width,
+            _ => 1,
         })
-        .unwrap_or(1);
+        .sum::<usize>();
 
     (lines, cols)
 }
 
-fn lines_enclosing_spans<'a>(source: &str, spans: impl Iterator<Item = &'a Span>) -> &str {
+/// Lays out one line of source code, `offset` is the byte index of the line in the source.
+/// `highlights` are the parts of the line returned by a [`Highlighter`].
+fn layout_line(
+    line: &str,
+    offset: usize,
+    highlights: &[StyledStr],
+    labels: &[(&Label, Color)],
+    config: &Config,
+) -> Styled<Element> {
+    let mut segments: Vec<(String, Style)> = vec![];
+
+    // Byte index in the line at which each highlighted part ends
+    let highlight_ends = highlights
+        .iter()
+        .scan(0, |end, part| {
+            *end += part.inner().len();
+            Some((*end, *part.style()))
+        })
+        .collect::<Vec<_>>();
+
+    for (index, grapheme) in line.grapheme_indices(true) {
+        let mut style = highlight_ends
+            .iter()
+            .find(|(end, _)| index < *end)
+            .map(|(_, style)| *style)
+            .unwrap_or_default();
+
+        let label = labels
+            .iter()
+            .find(|(label, _)| label.span.contains(&(offset + index)));
+
+        let mut text = grapheme.to_string();
+        if let Some((label, color)) = label {
+            style = config.label_highlight.apply(style, *color);
+
+            let visible_whitespace = label
+                .visible_whitespace
+                .unwrap_or(config.visible_whitespace);
+            if let Some(glyph) = whitespace_glyph(grapheme, config).filter(|_| visible_whitespace) {
+                // Glyphs do not take attributes like bold from the highlighted code around them
+                text = glyph;
+                style = config
+                    .label_highlight
+                    .apply(Style::default(), *color)
+                    .fg(*color);
+            }
+        }
+
+        match segments.last_mut() {
+            Some((last_text, last_style)) if *last_style == style => last_text.push_str(&text),
+            _ => segments.push((text, style)),
+        }
+    }
+
+    if segments.is_empty() {
+        return Element::inline("").styled(Style::default());
+    }
+
+    Element::hstack(
+        segments
+            .into_iter()
+            .map(|(text, style)| Element::inline(sanitize(&text, config.tab_width)).styled(style)),
+    )
+    .styled(Style::default())
+}
+
+/// Returns the labels of the view together with their color,
+/// labels without a color get one from the color generator of the view
+fn label_colors<'a, 'b, SourceId>(
+    view: &'b SourceView<'a, SourceId>,
+) -> Vec<(&'b Label<'a>, Color)> {
+    view.labels.iter().zip(view.label_colors()).collect()
+}
+
+/// Visible replacement for whitespace characters
+fn whitespace_glyph(grapheme: &str, config: &Config) -> Option<String> {
+    let theme = &config.theme;
+    match grapheme {
+        " " => Some(theme.space.into()),
+        "\t" => Some(format!(
+            "{}{}",
+            theme.tab,
+            " ".repeat(config.tab_width.saturating_sub(1))
+        )),
+        "\u{a0}" | "\u{202f}" => Some(theme.nbsp.into()),
+        _ => None,
+    }
+}
+
+/// Iterates over the lines of `text` together with their byte index,
+/// `offset` is added to every index.
+fn lines_with_offsets(text: &str, offset: usize) -> impl Iterator<Item = (usize, &str)> {
+    text.split_inclusive('\n').scan(offset, |start, line| {
+        let line_start = *start;
+        *start += line.len();
+        let line = line.strip_suffix('\n').unwrap_or(line);
+        Some((line_start, line.strip_suffix('\r').unwrap_or(line)))
+    })
+}
+
+/// Returns the span of all lines that contain part of the given spans
+fn lines_enclosing_spans<'a>(source: &str, spans: impl Iterator<Item = &'a Span>) -> Span {
     // Find smallest span that encloses all spans
     let (start, end) = spans.fold((source.len(), 0), |(start, end), span| {
         (start.min(span.start), end.max(span.end))
     });
 
-    let first_line_start = source[..start]
-        .rfind(|c| c == '\n')
-        .map(|i| i + 1)
-        .unwrap_or(start);
-    let offset_end = &source[end..].find(|c| c == '\n').unwrap_or(0);
+    let first_line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let offset_end = source[end..].find('\n').unwrap_or(source.len() - end);
     let last_line_end = end + offset_end;
 
-    &source[first_line_start..last_line_end]
+    first_line_start..last_line_end
+}
+
+/// Note about the invisible characters in labeled spans, if enabled by [`Config::with_invisible_chars_note`]
+fn invisible_chars_note<SourceId>(
+    report: &Report<SourceId>,
+    cache: &mut impl Cache<SourceId>,
+) -> Option<String> {
+    if !report.config.invisible_chars_note {
+        return None;
+    }
+    let chars = invisible_chars(&report.views, cache);
+    if chars.is_empty() {
+        return None;
+    }
+    let chars = chars
+        .iter()
+        .map(|c| format!("U+{:04X}", *c as u32))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Some(report.locale.invisible_chars(&chars))
+}
+
+/// Collects the invisible characters inside of all labeled spans, without duplicates
+fn invisible_chars<SourceId>(
+    views: &[SourceView<SourceId>],
+    cache: &mut impl Cache<SourceId>,
+) -> Vec<char> {
+    let mut chars = vec![];
+    for view in views {
+        let Ok(source) = cache.fetch(&view.source_id) else {
+            continue;
+        };
+        for label in &view.labels {
+            let Some(text) = source.get(label.span.clone()) else {
+                continue;
+            };
+            for c in text.chars().filter(|c| is_invisible(*c)) {
+                if !chars.contains(&c) {
+                    chars.push(c);
+                }
+            }
+        }
+    }
+    chars
+}
+
+/// Text of styled parts without their styles
+fn plain_text(parts: &[StyledStr]) -> String {
+    parts.iter().map(|part| part.inner().as_ref()).collect()
+}
+
+#[test]
+fn test_lines_cols() {
+    let source = "fn main() {\n\tlet a = 1;\n}";
+    assert_eq!(lines_cols(source, 0, 4), (1, 1));
+    // The start of a line is on that line, not after the end of the previous one
+    assert_eq!(lines_cols(source, 12, 4), (2, 1));
+    assert_eq!(lines_cols(source, 13, 4), (2, 5));
+    assert_eq!(lines_cols(source, source.len(), 4), (3, 2));
 }
@@ -1,10 +1,16 @@
-use super::{layout_report, Render};
-use crate::{Cache, Report};
+use super::{layout_explanation, layout_report, Render};
+use crate::{Cache, Explanation, Report};
 use std::io;
 
 pub struct PlainText<W: io::Write>(pub W);
 pub type PlainTextError = io::Error;
 
+impl<W: io::Write> PlainText<W> {
+    pub fn new(writer: W) -> Self {
+        Self(writer)
+    }
+}
+
 impl<W: io::Write> crate::Backend for PlainText<W> {
     type Error = PlainTextError;
 
@@ -14,7 +20,18 @@ impl<W: io::Write> crate::Backend for PlainText<W> {
         cache: &mut impl Cache<SourceId>,
     ) -> Result<(), Self::Error> {
         let element = layout_report(report, cache);
-        Self::render(&mut self.0, &element)
+        for line in self.render(&element) {
+            writeln!(self.0, "{line}")?;
+        }
+        Ok(())
+    }
+
+    fn write_explanation(&mut self, explanation: &Explanation) -> Result<(), Self::Error> {
+        let element = layout_explanation(explanation);
+        for line in self.render(&element) {
+            writeln!(self.0, "{line}")?;
+        }
+        Ok(())
     }
 }
 
@@ -1,75 +1,123 @@
 use crate::{style::Styled, tree::Element, Style};
-use std::io;
+use std::iter::Peekable;
 use unicode_segmentation::{GraphemeIndices, UnicodeSegmentation};
 use unicode_width::UnicodeWidthStr;
 
 pub(super) trait Render {
-    fn render(writer: &mut impl io::Write, element: &Styled<Element>) -> Result<(), io::Error> {
+    /// Renders the element into lines of text, without line endings
+    fn render(&self, element: &Styled<Element>) -> Vec<String> {
         let (_, height) = element_size(element);
         let mut lines = Vec::from_iter((0..height).map(|_| String::new()));
 
-        Self::render_element(&mut lines, element);
+        self.render_element(&mut lines, element, &Style::default());
 
-        for line in lines {
-            writeln!(writer, "{line}")?;
-        }
-        Ok(())
+        lines
     }
 
-    fn render_element(lines: &mut [String], element: &Styled<Element>) {
-        Self::write_style_prefix(lines.first_mut().unwrap(), element.style());
+    /// `parent` is the effective style of the parent element, unset attributes are inherited from it.
+    ///
+    /// Styles are only written around text, so the style of a parent
+    /// is restored by writing its effective style again after a child has been rendered.
+    fn render_element(&self, lines: &mut [String], element: &Styled<Element>, parent: &Style) {
+        let style = element.style().inherit(parent);
         match element.inner() {
-            Element::VStack { children, .. } => Self::render_vstack(lines, children),
-            Element::HStack { children, .. } => Self::render_hstack(lines, children),
-            Element::Box { children, .. } => Self::render_box(lines, element, children),
-            Element::Inline { text, .. } => lines[0].push_str(text),
+            Element::VStack { children, .. } => self.render_vstack(lines, children, &style),
+            Element::HStack { children, .. } => self.render_hstack(lines, children, &style),
+            Element::Box { children, .. } => self.render_box(lines, element, children, &style),
+            Element::Inline { text, .. } => self.render_text(&mut lines[0], text, &style),
+            Element::Link { text, target } => {
+                self.write_link_prefix(&mut lines[0], target);
+                self.render_text(&mut lines[0], text, &style);
+                self.write_link_suffix(&mut lines[0], target);
+            }
         }
-        Self::write_style_suffix(lines.last_mut().unwrap(), element.style());
     }
 
-    fn render_vstack(lines: &mut [String], elements: &[Styled<Element>]) {
+    fn render_vstack(&self, lines: &mut [String], elements: &[Styled<Element>], style: &Style) {
         let mut start = 0;
         for element in elements {
             let (_, height) = element_size(element);
-            Self::render_element(&mut lines[start..start + height], element);
+            self.render_element(&mut lines[start..start + height], element, style);
             start += height;
         }
     }
 
-    fn render_hstack(lines: &mut [String], elements: &[Styled<Element>]) {
+    fn render_hstack(&self, lines: &mut [String], elements: &[Styled<Element>], style: &Style) {
         for element in elements {
             fill_spaces(lines);
-            Self::render_element(lines, element);
+            self.render_element(lines, element, style);
         }
     }
 
-    fn render_box(lines: &mut [String], box_: &Styled<Element>, elements: &[Styled<Element>]) {
+    fn render_box(
+        &self,
+        lines: &mut [String],
+        box_: &Styled<Element>,
+        elements: &[Styled<Element>],
+        style: &Style,
+    ) {
         fill_spaces(lines);
 
         let (bow_width, _) = element_size(box_);
         for element in elements {
             if let Element::Inline { text } = element.inner() {
-                Self::write_style_prefix(lines.first_mut().unwrap(), element.style());
+                let style = element.style().inherit(style);
                 for (index, chunk) in WidthChunks::new(text, bow_width).enumerate() {
-                    lines[index].push_str(chunk);
+                    self.render_text(&mut lines[index], chunk, &style);
                 }
-                Self::write_style_suffix(lines.last_mut().unwrap(), element.style());
                 continue;
             }
-            Self::render_element(lines, element);
+            self.render_element(lines, element, style);
         }
     }
 
+    fn render_text(&self, line: &mut String, text: &str, style: &Style) {
+        self.write_style_prefix(line, style);
+        line.push_str(text);
+        self.write_style_suffix(line, style);
+    }
+
     // TODO Not sure whether this will actually hold up for html
-    fn write_style_prefix(_string: &mut String, _style: &Style) {}
-    fn write_style_suffix(_string: &mut String, _style: &Style) {}
+    fn write_style_prefix(&self, _string: &mut String, _style: &Style) {}
+    fn write_style_suffix(&self, _string: &mut String, _style: &Style) {}
+    fn write_link_prefix(&self, _string: &mut String, _target: &str) {}
+    fn write_link_suffix(&self, _string: &mut String, _target: &str) {}
 }
 
 fn fill_spaces(lines: &mut [String]) {
-    let max_width = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+    let max_width = lines
+        .iter()
+        .map(|line| visible_width(line))
+        .max()
+        .unwrap_or(0);
     for line in lines {
-        line.push_str(&" ".repeat(max_width - line.width()));
+        line.push_str(&" ".repeat(max_width - visible_width(line)));
+    }
+}
+
+/// Unicode width of a rendered line, without the escape sequences written by backends
+///
+/// Skips CSI sequences (`ESC [ ... final byte`) and OSC sequences (`ESC ] ... ESC \`).
+fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut rest = line;
+    while let Some(index) = rest.find('\x1B') {
+        width += rest[..index].width();
+        let sequence = &rest[index + 1..];
+        rest = match sequence.as_bytes().first() {
+            Some(b'[') => {
+                let end = sequence[1..]
+                    .find(|c: char| ('@'..='~').contains(&c))
+                    .map_or(sequence.len(), |end| end + 2);
+                &sequence[end..]
+            }
+            Some(b']') => sequence
+                .find("\x1B\\")
+                .map_or("", |end| &sequence[end + 2..]),
+            _ => sequence,
+        };
     }
+    width + rest.width()
 }
 
 // TODO Does it make sense to avoid recalculations of the sizes?
@@ -87,7 +135,7 @@ fn element_size(element: &Styled<Element>) -> (usize, usize) {
         Element::Box {
             children, width, ..
         } => box_size(children, width),
-        Element::Inline { text, .. } => (text.width(), 1),
+        Element::Inline { text, .. } | Element::Link { text, .. } => (text.width(), 1),
     }
 }
 
@@ -112,10 +160,9 @@ fn box_size(children: &[Styled<Element>], max_width: &Option<usize>) -> (usize,
     for element in children {
         let (elem_width, elem_height) = element_size(element);
 
-        if matches!(element.inner(), Element::Inline { .. }) {
-            // Break text up over multiple rows
-            box_height += (row_width + elem_width - box_width) / *box_width;
-            row_width = (row_width + elem_width) % *box_width;
+        if let Element::Inline { text } = element.inner() {
+            // Text is broken up over rows starting at the first one, the same way it is rendered
+            box_height = box_height.max(WidthChunks::new(text, *box_width).count());
             continue;
         }
 
@@ -137,9 +184,11 @@ fn box_size(children: &[Styled<Element>], max_width: &Option<usize>) -> (usize,
 #[must_use]
 /// Splits a &[`str`] into chunks,
 /// so that the unicode width is at most the given width
+///
+/// Wide graphemes (like CJK characters) are never split or placed partially into a chunk,
+/// they start a new chunk instead. Only a grapheme that is wider than the chunk width
+/// gets a chunk of its own that exceeds the width.
 struct WidthChunks<'a> {
-    /// How many chunks this iterator will produce.
-    len: usize,
     /// The maximum width of one chunk.
     width: usize,
     /// The starting byte index of the next chunk.
@@ -148,17 +197,16 @@ struct WidthChunks<'a> {
     slice: &'a str,
     /// Iterator over its graphames,
     /// so we dont have to reconstruct it on every next call.
-    graphemes: GraphemeIndices<'a>,
+    graphemes: Peekable<GraphemeIndices<'a>>,
 }
 
 impl<'a> WidthChunks<'a> {
     fn new(slice: &'a str, width: usize) -> Self {
         Self {
-            len: slice.width().div_ceil(width),
             width,
             start: 0,
             slice,
-            graphemes: slice.grapheme_indices(true),
+            graphemes: slice.grapheme_indices(true).peekable(),
         }
     }
 }
@@ -173,23 +221,20 @@ impl<'a> Iterator for WidthChunks<'a> {
 
         let mut width = 0;
         let mut end = self.start;
-        for (index, grapheme) in &mut self.graphemes {
-            width += grapheme.width();
-            end = index + grapheme.len();
-            if width >= self.width {
+        while let Some((index, grapheme)) = self.graphemes.peek() {
+            let grapheme_width = grapheme.width();
+            if end > self.start && width + grapheme_width > self.width {
                 break;
             }
+            width += grapheme_width;
+            end = index + grapheme.len();
+            self.graphemes.next();
         }
 
         let result = &self.slice[self.start..end];
         self.start = end;
         Some(result)
     }
-
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        // TODO Is this actually correct?
-        (self.len, Some(self.len))
-    }
 }
 
 #[test]
@@ -227,6 +272,26 @@ fn test_width_chunks() {
         WidthChunks::new("#_#_#_#__#_#_#_##_#_#_#__#_#_#_#", 8).collect::<Vec<_>>(),
         ["#_#_#_#_", "_#_#_#_#", "#_#_#_#_", "_#_#_#_#"]
     );
+
+    // Wide characters are moved to the next chunk instead of exceeding the width
+    assert_eq!(
+        WidthChunks::new("ab警告です", 5).collect::<Vec<_>>(),
+        ["ab警", "告で", "す"]
+    );
+    assert_eq!(
+        WidthChunks::new("警告", 1).collect::<Vec<_>>(),
+        ["警", "告"]
+    );
+}
+
+#[test]
+fn test_visible_width() {
+    assert_eq!(visible_width("plain"), 5);
+    assert_eq!(visible_width("\x1B[1;31mError\x1B[0m: 警告"), 11);
+    assert_eq!(
+        visible_width("\x1B]8;;file:///src/main.rs\x1B\\main.rs\x1B]8;;\x1B\\"),
+        7
+    );
 }
 
 #[test]
@@ -254,9 +319,11 @@ fn test_rendering() {
     .styled(Style::default());
     assert_eq!(element_size(&element), (11, 6));
 
-    let mut writer = Vec::new();
-    TestBackend::render(&mut writer, &element).unwrap();
-    let output = String::from_utf8(writer).unwrap();
+    let output = TestBackend
+        .render(&element)
+        .into_iter()
+        .map(|line| line + "\n")
+        .collect::<String>();
 
     // Expected:
     // test1
@@ -267,3 +334,18 @@ fn test_rendering() {
     // test3
     insta::assert_snapshot!(output);
 }
+
+#[test]
+fn test_wide_chars_in_box() {
+    struct TestBackend;
+    impl Render for TestBackend {}
+
+    // Wide characters do not fit at the end of a row, so the text needs three rows instead of two
+    let element = Element::box_(
+        [Element::inline("ab警告です").styled(Style::default())],
+        Some(5),
+    )
+    .styled(Style::default());
+    assert_eq!(element_size(&element), (5, 3));
+    assert_eq!(TestBackend.render(&element), ["ab警", "告で", "す"]);
+}
@@ -0,0 +1,71 @@
+use std::borrow::Cow;
+
+/// Returns a visible replacement for characters that would corrupt the terminal
+/// or hide source code (Trojan Source), or `None` if the character can be displayed as is.
+///
+/// Tabs are not escaped here, they are expanded by [`sanitize`].
+pub(super) fn escape_char(c: char) -> Option<Cow<'static, str>> {
+    match c {
+        '\t' => None,
+        // Control Pictures block: ␀ ␁ ... ␟
+        '\0'..='\x1f' => Some(char::from_u32(0x2400 + c as u32).unwrap().to_string().into()),
+        '\x7f' => Some("␡".into()),
+        // C1 controls
+        '\u{80}'..='\u{9f}'
+        // Zero width space, non-joiner, joiner and directional marks
+        | '\u{200b}'..='\u{200f}'
+        // Line and paragraph separators
+        | '\u{2028}'..='\u{2029}'
+        // Bidi embeddings and overrides
+        | '\u{202a}'..='\u{202e}'
+        // Word joiner and invisible operators
+        | '\u{2060}'..='\u{2064}'
+        // Bidi isolates
+        | '\u{2066}'..='\u{2069}'
+        | '\u{061c}'
+        | '\u{feff}' => Some(format!("<U+{:04X}>", c as u32).into()),
+        _ => None,
+    }
+}
+
+/// Line breaks are not considered invisible, as they are displayed as separate lines.
+pub(super) fn is_invisible(c: char) -> bool {
+    !matches!(c, '\n' | '\r') && escape_char(c).is_some()
+}
+
+/// Replaces all characters that can not be displayed as is with visible escapes
+/// and expands tabs to `tab_width` spaces.
+pub(super) fn sanitize(text: &str, tab_width: usize) -> Cow<'_, str> {
+    if !text.chars().any(|c| c == '\t' || is_invisible(c)) {
+        return text.into();
+    }
+
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if c == '\t' {
+            result.extend(std::iter::repeat_n(' ', tab_width));
+        } else if let Some(escaped) = escape_char(c) {
+            result.push_str(&escaped);
+        } else {
+            result.push(c);
+        }
+    }
+    result.into()
+}
+
+#[test]
+fn test_sanitize() {
+    assert!(matches!(sanitize("let a = 1;", 4), Cow::Borrowed(_)));
+    assert_eq!(sanitize("\tlet a = 1;", 2), "  let a = 1;");
+    assert_eq!(sanitize("a\0b\x1bc\x7f", 4), "a␀b␛c␡");
+    assert_eq!(
+        sanitize(
+            "if access_level != \"user\u{202e} \u{2066}// Check if admin\u{2069} \u{2066}\" {",
+            4
+        ),
+        "if access_level != \"user<U+202E> <U+2066>// Check if admin<U+2069> <U+2066>\" {"
+    );
+    assert_eq!(sanitize("a\u{200d}b\u{feff}", 4), "a<U+200D>b<U+FEFF>");
+    // Visible non ascii characters are left alone
+    assert_eq!(sanitize("ä → 日本", 4), "ä → 日本");
+}
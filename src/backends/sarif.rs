@@ -0,0 +1,251 @@
+use super::json::Value;
+use super::{is_primary, lines_cols, plain_text};
+use crate::{
+    percent_encode, Backend, Cache, Catalog, DiagnosticDef, Report, ReportKind, Span, StyledStr,
+};
+use std::io;
+
+/// Collects reports into a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log,
+/// which is written by [`Backend::finish`]. [`Emitter::finish`](crate::Emitter::finish) calls it,
+/// otherwise it has to be called after the last report, or the reports are lost when the backend is dropped.
+///
+/// Every code becomes a rule, with the url of the report as `helpUri`
+/// and the explanation of the code in [`Sarif::with_catalog`] as `help`.
+/// Labels that contain the location of their view become `locations`, the others `relatedLocations`.
+/// If none of them does, the first label is the location.
+/// Lines and columns of regions are counted like in [`Json`](crate::Json).
+/// Comments are appended to the message, reports have no suggestions so results have no `fixes`.
+pub struct Sarif<'a, W: io::Write>(pub W, SarifLog<'a>);
+pub type SarifError = io::Error;
+
+/// Log that is collected until [`Backend::finish`]
+struct SarifLog<'a> {
+    name: String,
+    version: Option<String>,
+    information_uri: Option<String>,
+    catalog: Option<&'a Catalog>,
+    rules: Vec<Rule>,
+    results: Vec<Value>,
+}
+
+struct Rule {
+    id: String,
+    url: Option<String>,
+}
+
+impl<'a, W: io::Write> Sarif<'a, W> {
+    /// `name` is the name of the tool that produced the reports
+    pub fn new(writer: W, name: impl Into<String>) -> Self {
+        Self(
+            writer,
+            SarifLog {
+                name: name.into(),
+                version: None,
+                information_uri: None,
+                catalog: None,
+                rules: vec![],
+                results: vec![],
+            },
+        )
+    }
+
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.set_version(version);
+        self
+    }
+
+    pub fn set_version(&mut self, version: impl Into<String>) {
+        self.1.version = Some(version.into());
+    }
+
+    /// Link to the documentation of the tool
+    pub fn with_information_uri(mut self, uri: impl Into<String>) -> Self {
+        self.set_information_uri(uri);
+        self
+    }
+
+    pub fn set_information_uri(&mut self, uri: impl Into<String>) {
+        self.1.information_uri = Some(uri.into());
+    }
+
+    /// Fills the `help` of rules whose code has an explanation in the catalog
+    pub fn with_catalog(mut self, catalog: &'a Catalog) -> Self {
+        self.1.catalog = Some(catalog);
+        self
+    }
+
+    pub fn set_catalog(&mut self, catalog: &'a Catalog) {
+        self.1.catalog = Some(catalog);
+    }
+
+    /// Index of the rule with the code, which is added if it does not exist yet
+    fn rule(&mut self, code: &str) -> usize {
+        if let Some(index) = self.1.rules.iter().position(|rule| rule.id == code) {
+            return index;
+        }
+        self.1.rules.push(Rule {
+            id: code.into(),
+            url: None,
+        });
+        self.1.rules.len() - 1
+    }
+}
+
+impl<W: io::Write> Backend for Sarif<'_, W> {
+    type Error = SarifError;
+
+    /// Adds the report to the log, nothing is written until [`Backend::finish`]
+    fn write<SourceId>(
+        &mut self,
+        report: &Report<SourceId>,
+        cache: &mut impl Cache<SourceId>,
+    ) -> Result<(), Self::Error> {
+        let mut result = vec![];
+        if let Some(code) = &report.code {
+            let index = self.rule(code);
+            if let Some(url) = &report.url {
+                self.1.rules[index].url.get_or_insert_with(|| url.clone());
+            }
+            result.push(("ruleId", code.as_str().into()));
+            result.push(("ruleIndex", index.into()));
+        }
+        result.push(("level", level(report.kind).into()));
+
+        let mut message = plain_text(&report.message);
+        for (kind, comment) in &report.comments {
+            let kind = report.locale.kind_name(*kind);
+            message.push_str(&format!("\n{kind}: {}", plain_text(comment)));
+        }
+        result.push(("message", text(message)));
+
+        let mut locations = vec![];
+        let mut related_locations = vec![];
+        for view in &report.views {
+            let uri = cache
+                .display_id(&view.source_id)
+                .map(|id| percent_encode(&id.to_string()));
+            let source = cache.fetch(&view.source_id).ok();
+
+            if view.labels.is_empty() {
+                let span = view.location..view.location;
+                locations.push(location(uri.as_deref(), source, &span, None));
+            }
+            for (index, label) in view.labels.iter().enumerate() {
+                let message = label.message.as_deref();
+                let mut location = location(uri.as_deref(), source, &label.span, message);
+                if is_primary(view, index) {
+                    locations.push(location);
+                } else {
+                    if let Value::Object(fields) = &mut location {
+                        fields.insert(0, ("id", related_locations.len().into()));
+                    }
+                    related_locations.push(location);
+                }
+            }
+        }
+        result.push(("locations", Value::Array(locations)));
+        if !related_locations.is_empty() {
+            result.push(("relatedLocations", Value::Array(related_locations)));
+        }
+
+        self.1.results.push(Value::Object(result));
+        Ok(())
+    }
+
+    /// Writes the log with all collected reports, afterwards the backend starts a new log
+    fn finish(&mut self) -> Result<(), Self::Error> {
+        let catalog = self.1.catalog;
+        let rules = self.1.rules.drain(..).map(|rule| {
+            let explanation = catalog
+                .and_then(|catalog| catalog.get(&rule.id))
+                .and_then(DiagnosticDef::explanation);
+            let mut fields = vec![("id", rule.id.into())];
+            if let Some(url) = rule.url {
+                fields.push(("helpUri", url.into()));
+            }
+            if let Some(explanation) = explanation {
+                fields.push(("help", text(explanation)));
+            }
+            Value::Object(fields)
+        });
+
+        let mut driver = vec![("name", self.1.name.as_str().into())];
+        if let Some(version) = &self.1.version {
+            driver.push(("version", version.as_str().into()));
+        }
+        if let Some(uri) = &self.1.information_uri {
+            driver.push(("informationUri", uri.as_str().into()));
+        }
+        driver.push(("rules", Value::Array(rules.collect())));
+
+        let run = Value::Object(vec![
+            (
+                "tool",
+                Value::Object(vec![("driver", Value::Object(driver))]),
+            ),
+            ("columnKind", "unicodeCodePoints".into()),
+            ("results", Value::Array(std::mem::take(&mut self.1.results))),
+        ]);
+        let log = Value::Object(vec![
+            (
+                "$schema",
+                "https://json.schemastore.org/sarif-2.1.0.json".into(),
+            ),
+            ("version", "2.1.0".into()),
+            ("runs", Value::Array(vec![run])),
+        ]);
+        writeln!(self.0, "{log}")?;
+        self.0.flush()
+    }
+}
+
+/// SARIF has no levels for help, custom kinds are not applicable to any level
+fn level(kind: ReportKind) -> &'static str {
+    match kind {
+        ReportKind::Error => "error",
+        ReportKind::Warning => "warning",
+        ReportKind::Help | ReportKind::Note => "note",
+        ReportKind::Custom(..) => "none",
+    }
+}
+
+/// Message object with plain text
+fn text(text: impl Into<Value>) -> Value {
+    Value::Object(vec![("text", text.into())])
+}
+
+/// Regions of sources that can not be fetched only have byte offsets
+fn location(
+    uri: Option<&str>,
+    source: Option<&str>,
+    span: &Span,
+    message: Option<&[StyledStr]>,
+) -> Value {
+    let mut region = vec![];
+    if let Some(source) = source.filter(|source| source.get(span.clone()).is_some()) {
+        let (start_line, start_column) = lines_cols(source, span.start, 1);
+        let (end_line, end_column) = lines_cols(source, span.end, 1);
+        region.extend([
+            ("startLine", start_line.into()),
+            ("startColumn", start_column.into()),
+            ("endLine", end_line.into()),
+            ("endColumn", end_column.into()),
+        ]);
+    }
+    region.extend([
+        ("byteOffset", span.start.into()),
+        ("byteLength", span.len().into()),
+    ]);
+
+    let mut physical_location = vec![];
+    if let Some(uri) = uri {
+        physical_location.push(("artifactLocation", Value::Object(vec![("uri", uri.into())])));
+    }
+    physical_location.push(("region", Value::Object(region)));
+
+    let mut location = vec![("physicalLocation", Value::Object(physical_location))];
+    if let Some(message) = message {
+        location.push(("message", text(plain_text(message))));
+    }
+    Value::Object(location)
+}
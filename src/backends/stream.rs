@@ -0,0 +1,111 @@
+use super::ansi::AnsiOptions;
+use super::{Ansi, PlainText};
+use crate::{Backend, Cache, ColorChoice, ColorDepth, Explanation, Report};
+use std::io::{self, IsTerminal};
+
+/// Writes to a terminal using the [`Ansi`] backend and to anything else using the [`PlainText`] backend,
+/// depending on the [`ColorChoice`] and the environment.
+pub struct Stream<W: io::Write> {
+    inner: StreamInner<W>,
+}
+pub type StreamError = io::Error;
+
+enum StreamInner<W: io::Write> {
+    Ansi(Ansi<W>, AnsiOptions),
+    PlainText(PlainText<W>),
+}
+
+impl<W: io::Write + IsTerminal> Stream<W> {
+    /// Detects the color depth from the color choice, whether the writer is a terminal and the environment
+    pub fn from_color_choice(writer: W, color_choice: ColorChoice) -> Self {
+        let color_depth = color_choice.color_depth(writer.is_terminal());
+        Self::new(writer, color_depth)
+    }
+}
+
+impl Stream<io::Stdout> {
+    pub fn stdout(color_choice: ColorChoice) -> Self {
+        Self::from_color_choice(io::stdout(), color_choice)
+    }
+}
+
+impl Stream<io::Stderr> {
+    pub fn stderr(color_choice: ColorChoice) -> Self {
+        Self::from_color_choice(io::stderr(), color_choice)
+    }
+}
+
+impl<W: io::Write> Stream<W> {
+    /// Skips the detection, [`ColorDepth::None`] selects the [`PlainText`] backend
+    pub fn new(writer: W, color_depth: ColorDepth) -> Self {
+        let inner = match color_depth {
+            ColorDepth::None => StreamInner::PlainText(PlainText(writer)),
+            color_depth => StreamInner::Ansi(
+                Ansi(writer),
+                AnsiOptions {
+                    color_depth,
+                    hyperlinks: false,
+                },
+            ),
+        };
+        Self { inner }
+    }
+
+    /// Writes OSC 8 hyperlinks for source names and codes when the [`Ansi`] backend is selected,
+    /// see [`Cache::link`] and [`Report::with_url`]. Disabled by default.
+    pub fn with_hyperlinks(mut self, enabled: bool) -> Self {
+        self.set_hyperlinks(enabled);
+        self
+    }
+
+    pub fn set_hyperlinks(&mut self, enabled: bool) {
+        if let StreamInner::Ansi(_, options) = &mut self.inner {
+            options.hyperlinks = enabled;
+        }
+    }
+
+    pub fn is_ansi(&self) -> bool {
+        matches!(self.inner, StreamInner::Ansi(..))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.inner {
+            StreamInner::Ansi(ansi, _) => ansi.0.flush(),
+            StreamInner::PlainText(plaintext) => plaintext.0.flush(),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        match self.inner {
+            StreamInner::Ansi(ansi, _) => ansi.0,
+            StreamInner::PlainText(plaintext) => plaintext.0,
+        }
+    }
+}
+
+impl<W: io::Write> Backend for Stream<W> {
+    type Error = StreamError;
+
+    /// Flushes the writer after each report, so reports show up while the program runs
+    fn write<SourceId>(
+        &mut self,
+        report: &Report<SourceId>,
+        cache: &mut impl Cache<SourceId>,
+    ) -> Result<(), Self::Error> {
+        match &mut self.inner {
+            StreamInner::Ansi(ansi, options) => ansi.write_with_options(report, cache, *options)?,
+            StreamInner::PlainText(plaintext) => plaintext.write(report, cache)?,
+        }
+        self.flush()
+    }
+
+    fn write_explanation(&mut self, explanation: &Explanation) -> Result<(), Self::Error> {
+        match &mut self.inner {
+            StreamInner::Ansi(ansi, options) => {
+                ansi.write_explanation_with_options(explanation, *options)?
+            }
+            StreamInner::PlainText(plaintext) => plaintext.write_explanation(explanation)?,
+        }
+        self.flush()
+    }
+}
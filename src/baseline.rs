@@ -0,0 +1,292 @@
+use crate::{Cache, Report};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Identifies a report across runs, even if the surrounding source code moved
+///
+/// Computed from the code (or the message of reports without a code), the displayed source ids,
+/// the text of the labeled lines and of the lines directly before and after them,
+/// without any byte offsets or line numbers.
+/// Whitespace at the start and end of lines is ignored, so reindenting code keeps the fingerprint.
+pub struct Fingerprint(u64);
+
+impl Fingerprint {
+    pub fn new<SourceId>(report: &Report<SourceId>, cache: &mut impl Cache<SourceId>) -> Self {
+        let mut hasher = Fnv1a::default();
+        match &report.code {
+            Some(code) => hasher.write(code),
+            None => {
+                for part in &report.message {
+                    hasher.write(part.inner());
+                }
+            }
+        }
+
+        for view in &report.views {
+            let name = cache.display_id(&view.source_id).map(|id| id.to_string());
+            hasher.write(name.as_deref().unwrap_or_default());
+
+            let Ok(source) = cache.fetch(&view.source_id) else {
+                continue;
+            };
+            for label in &view.labels {
+                let Some(text) = source.get(label.span.clone()) else {
+                    continue;
+                };
+                hasher.write(text.trim());
+
+                // The labeled lines and their neighbours, so equal text in different places differs
+                let start = line_start(source, label.span.start);
+                let start = start
+                    .checked_sub(1)
+                    .map_or(0, |end| line_start(source, end));
+                let end = line_end(source, label.span.end);
+                let end = match end < source.len() {
+                    true => line_end(source, end + 1),
+                    false => end,
+                };
+                for line in source[start..end].lines() {
+                    hasher.write(line.trim());
+                }
+            }
+        }
+        Fingerprint(hasher.0)
+    }
+}
+
+/// Byte index of the start of the line that contains `index`
+fn line_start(source: &str, index: usize) -> usize {
+    source[..index].rfind('\n').map_or(0, |i| i + 1)
+}
+
+/// Byte index of the line break that ends the line containing `index`, or the end of the source
+fn line_end(source: &str, index: usize) -> usize {
+    source[index..]
+        .find('\n')
+        .map_or(source.len(), |i| index + i)
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+impl FromStr for Fingerprint {
+    type Err = ParseBaselineError;
+
+    /// Parses the 16 hex digits written by [`Display`](fmt::Display)
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = || ParseBaselineError {
+            line: 0,
+            input: input.into(),
+        };
+        // `from_str_radix` would also accept a sign
+        if input.len() != 16 || !input.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+        u64::from_str_radix(input, 16)
+            .map(Fingerprint)
+            .map_err(|_| error())
+    }
+}
+
+/// 64 bit FNV-1a, which unlike the hashers of the standard library is stable across versions
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    /// Writes the text and a separator, so that ("ab", "c") and ("a", "bc") differ
+    fn write(&mut self, text: &str) {
+        for byte in text.bytes().chain([0xff]) {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// [`Fingerprint`]s of known reports, the [`Emitter`](crate::Emitter) only writes reports that are not in it
+///
+/// Every occurrence of a fingerprint hides one report, so new reports that are identical
+/// to known ones are still written.
+/// The file format has one fingerprint per occurrence and line, empty lines and lines starting with `#` are ignored.
+pub struct Baseline {
+    /// Number of occurrences of each fingerprint
+    fingerprints: BTreeMap<Fingerprint, usize>,
+}
+
+impl Baseline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ParseBaselineError> {
+        let mut baseline = Self::new();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fingerprint =
+                line.parse()
+                    .map_err(|error: ParseBaselineError| ParseBaselineError {
+                        line: index + 1,
+                        ..error
+                    })?;
+            baseline.insert(fingerprint);
+        }
+        Ok(baseline)
+    }
+
+    /// Adds an occurrence of the fingerprint, returns whether it was not yet in the baseline
+    pub fn insert(&mut self, fingerprint: Fingerprint) -> bool {
+        let count = self.fingerprints.entry(fingerprint).or_default();
+        *count += 1;
+        *count == 1
+    }
+
+    /// Removes an occurrence of the fingerprint, returns whether there was one
+    pub fn remove(&mut self, fingerprint: Fingerprint) -> bool {
+        let Some(count) = self.fingerprints.get_mut(&fingerprint) else {
+            return false;
+        };
+        *count -= 1;
+        if *count == 0 {
+            self.fingerprints.remove(&fingerprint);
+        }
+        true
+    }
+
+    pub fn contains(&self, fingerprint: Fingerprint) -> bool {
+        self.fingerprints.contains_key(&fingerprint)
+    }
+
+    /// Number of occurrences of the fingerprint
+    pub fn count(&self, fingerprint: Fingerprint) -> usize {
+        self.fingerprints.get(&fingerprint).copied().unwrap_or(0)
+    }
+
+    /// Number of occurrences of all fingerprints
+    pub fn len(&self) -> usize {
+        self.fingerprints.values().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fingerprints.is_empty()
+    }
+
+    /// Writes the baseline in the format read by [`Baseline::parse`], sorted so it diffs well
+    pub fn write(&self, mut writer: impl io::Write) -> io::Result<()> {
+        for (fingerprint, count) in &self.fingerprints {
+            for _ in 0..*count {
+                writeln!(writer, "{fingerprint}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Extend<Fingerprint> for Baseline {
+    fn extend<T: IntoIterator<Item = Fingerprint>>(&mut self, iter: T) {
+        for fingerprint in iter {
+            self.insert(fingerprint);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBaselineError {
+    /// Line in the baseline file, 0 if a single fingerprint was parsed
+    line: usize,
+    input: String,
+}
+
+impl fmt::Display for ParseBaselineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid fingerprint `{}`, expected 16 hex digits",
+            self.input
+        )?;
+        if self.line > 0 {
+            write!(f, " on line {}", self.line)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseBaselineError {}
+
+#[test]
+fn test_fingerprint() {
+    use crate::{Label, ReportKind, SourceView};
+
+    let report = |location: usize| {
+        Report::new(ReportKind::Warning)
+            .with_code("W01")
+            .with_message(format!("unused variable at {location}"))
+            .with_view(
+                SourceView::new("main.rs", location).with_label(Label::new(location..location + 1)),
+            )
+    };
+    let before = "fn main() {\n    let x = 1;\n}\n";
+    let after = "// Comment\nfn main() {\n\tlet x = 1;\n}\n";
+
+    let fingerprint = Fingerprint::new(&report(20), &mut vec![("main.rs", before)]);
+    // Moved and reindented code keeps the fingerprint
+    assert_eq!(
+        Fingerprint::new(&report(28), &mut vec![("main.rs", after)]),
+        fingerprint
+    );
+    // A different label does not
+    assert_ne!(
+        Fingerprint::new(&report(16), &mut vec![("main.rs", before)]),
+        fingerprint
+    );
+    // Neither does a changed line around the labeled one
+    let renamed = "fn other() {\n    let x = 1;\n}\n";
+    assert_ne!(
+        Fingerprint::new(&report(21), &mut vec![("main.rs", renamed)]),
+        fingerprint
+    );
+    assert_eq!(fingerprint.to_string().parse(), Ok(fingerprint));
+}
+
+#[test]
+fn test_baseline() {
+    let mut baseline = Baseline::new();
+    baseline.extend([Fingerprint(0xff), Fingerprint(1), Fingerprint(0xff)]);
+    assert_eq!((baseline.len(), baseline.count(Fingerprint(0xff))), (3, 2));
+    let mut file = b"# Baseline\n".to_vec();
+    baseline.write(&mut file).unwrap();
+    let file = String::from_utf8(file).unwrap();
+
+    assert_eq!(
+        file,
+        "# Baseline\n0000000000000001\n00000000000000ff\n00000000000000ff\n"
+    );
+    assert_eq!(Baseline::parse(&file), Ok(baseline.clone()));
+
+    assert!(baseline.remove(Fingerprint(0xff)));
+    assert!(baseline.contains(Fingerprint(0xff)));
+    assert!(baseline.remove(Fingerprint(0xff)));
+    assert!(!baseline.contains(Fingerprint(0xff)));
+    assert!(!baseline.remove(Fingerprint(0xff)));
+
+    assert_eq!(
+        Baseline::parse("0000000000000001\n\nxyz")
+            .unwrap_err()
+            .to_string(),
+        "invalid fingerprint `xyz`, expected 16 hex digits on line 3"
+    );
+    assert!("+00000000000000f".parse::<Fingerprint>().is_err());
+}
@@ -0,0 +1,479 @@
+use crate::{Backend, Config, English, Locale, Report, ReportKind};
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy)]
+/// Definition of one diagnostic, usually written as a `static` and registered in a [`Catalog`]
+///
+/// The message is a template where `{name}` is replaced with the argument called `name`,
+/// `{{` and `}}` are written as literal braces:
+///
+/// ```
+/// # use ariadne_next::{DiagnosticDef, ReportKind};
+/// static E0412: DiagnosticDef = DiagnosticDef::new(
+///     "E0412",
+///     ReportKind::Error,
+///     "cannot find type `{name}` in this scope",
+/// )
+/// .with_url("https://doc.rust-lang.org/error_codes/E0412.html");
+///
+/// let report = E0412.report::<&str>(&[("name", &"Nat")]).unwrap();
+/// ```
+pub struct DiagnosticDef {
+    pub(crate) code: &'static str,
+    pub(crate) kind: ReportKind,
+    pub(crate) message: &'static str,
+    /// Long explanation of the diagnostic, empty if there is none
+    pub(crate) explanation: &'static str,
+    pub(crate) url: Option<&'static str>,
+}
+
+impl DiagnosticDef {
+    pub const fn new(code: &'static str, kind: ReportKind, message: &'static str) -> Self {
+        Self {
+            code,
+            kind,
+            message,
+            explanation: "",
+            url: None,
+        }
+    }
+
+    pub const fn with_explanation(mut self, explanation: &'static str) -> Self {
+        self.explanation = explanation;
+        self
+    }
+
+    /// Link to the documentation of the diagnostic
+    pub const fn with_url(mut self, url: &'static str) -> Self {
+        self.url = Some(url);
+        self
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    pub fn kind(&self) -> ReportKind {
+        self.kind
+    }
+
+    pub fn explanation(&self) -> Option<&'static str> {
+        Some(self.explanation).filter(|explanation| !explanation.is_empty())
+    }
+
+    pub fn url(&self) -> Option<&'static str> {
+        self.url
+    }
+
+    /// The long explanation, which can be written with any [`Backend`]
+    pub fn explain(&self) -> Option<Explanation<'static>> {
+        self.explanation()
+            .map(|text| Explanation::new(self.code, text))
+    }
+
+    /// Fills the message template with the arguments, arguments that are not used are ignored
+    pub fn message(&self, args: &[(&str, &dyn fmt::Display)]) -> Result<String, TemplateError> {
+        let error = |reason| TemplateError {
+            code: self.code,
+            reason,
+        };
+
+        let mut message = String::new();
+        let mut rest = self.message;
+        while let Some(index) = rest.find(['{', '}']) {
+            message.push_str(&rest[..index]);
+            let (brace, after) = rest[index..].split_at(1);
+            if after.starts_with(brace) {
+                message.push_str(brace);
+                rest = &after[1..];
+                continue;
+            }
+            if brace == "}" {
+                return Err(error(TemplateErrorReason::UnmatchedBrace));
+            }
+
+            let Some(end) = after.find('}') else {
+                return Err(error(TemplateErrorReason::UnmatchedBrace));
+            };
+            let name = &after[..end];
+            let Some((_, value)) = args.iter().find(|(arg, _)| *arg == name) else {
+                return Err(error(TemplateErrorReason::MissingArgument(name.into())));
+            };
+            message.push_str(&value.to_string());
+            rest = &after[end + 1..];
+        }
+        message.push_str(rest);
+        Ok(message)
+    }
+
+    /// Creates a report with the code, kind, url and filled message template of this definition
+    pub fn report<'a, SourceId>(
+        &self,
+        args: &[(&str, &dyn fmt::Display)],
+    ) -> Result<Report<'a, SourceId>, TemplateError> {
+        let mut report = Report::new(self.kind)
+            .with_code(self.code)
+            .with_message(self.message(args)?);
+        if let Some(url) = self.url {
+            report.set_url(url);
+        }
+        Ok(report)
+    }
+}
+
+#[derive(Debug, Clone)]
+/// Registry of [`DiagnosticDef`]s by their code
+pub struct Catalog {
+    defs: BTreeMap<&'static str, DiagnosticDef>,
+    /// Command that shows an explanation, mentioned in [`Catalog::explain_footer`]
+    explain_command: String,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Self {
+            defs: BTreeMap::new(),
+            explain_command: "--explain".into(),
+        }
+    }
+}
+
+impl Catalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Command that shows an explanation when followed by a code, like `rustc --explain`
+    pub fn with_explain_command(mut self, command: impl Into<String>) -> Self {
+        self.explain_command = command.into();
+        self
+    }
+
+    pub fn set_explain_command(&mut self, command: impl Into<String>) {
+        self.explain_command = command.into();
+    }
+
+    /// Replaces a previous definition with the same code
+    pub fn with_def(mut self, def: DiagnosticDef) -> Self {
+        self.add_def(def);
+        self
+    }
+
+    /// Replaces a previous definition with the same code
+    pub fn add_def(&mut self, def: DiagnosticDef) {
+        self.defs.insert(def.code, def);
+    }
+
+    pub fn get(&self, code: &str) -> Option<&DiagnosticDef> {
+        self.defs.get(code)
+    }
+
+    /// All definitions, ordered by their code
+    pub fn defs(&self) -> impl Iterator<Item = &DiagnosticDef> {
+        self.defs.values()
+    }
+
+    /// Creates a report from the definition with the given code, see [`DiagnosticDef::report`]
+    pub fn report<'a, SourceId>(
+        &self,
+        code: &str,
+        args: &[(&str, &dyn fmt::Display)],
+    ) -> Result<Report<'a, SourceId>, TemplateError> {
+        match self.get(code) {
+            Some(def) => def.report(args),
+            None => Err(TemplateError {
+                code: "",
+                reason: TemplateErrorReason::UnknownCode(code.into()),
+            }),
+        }
+    }
+
+    /// The long explanation of the definition with the given code, see [`DiagnosticDef::explain`]
+    pub fn explain(&self, code: &str) -> Option<Explanation<'static>> {
+        self.get(code)?.explain()
+    }
+
+    /// Creates a note listing the codes that have an explanation,
+    /// with a hint on how to show them. Codes are listed in the order they were seen.
+    ///
+    /// Returns `None` if none of the codes has an explanation.
+    pub fn explain_footer<'a, 'b, SourceId>(
+        &self,
+        codes: impl IntoIterator<Item = &'b str>,
+        locale: &'a dyn Locale,
+    ) -> Option<Report<'a, SourceId>> {
+        let mut explained = Vec::<&str>::new();
+        let mut kinds = Vec::new();
+        for code in codes {
+            let Some(def) = self.get(code).filter(|def| def.explanation().is_some()) else {
+                continue;
+            };
+            if !explained.contains(&code) {
+                explained.push(code);
+                kinds.push(def.kind);
+            }
+        }
+
+        let first = *explained.first()?;
+        // The wording depends on the kind if all codes have the same one
+        let kind = Some(kinds[0]).filter(|first| kinds.iter().all(|kind| kind == first));
+        let hint = locale.explain_hint(&self.explain_command, first, explained.len(), kind);
+        let report = Report::new(ReportKind::Note).with_locale(locale);
+        Some(match explained.len() {
+            1 => report.with_message(hint),
+            _ => report
+                .with_message(locale.explained_codes(kind, &explained))
+                .with_comment(ReportKind::Help, hint),
+        })
+    }
+}
+
+impl FromIterator<DiagnosticDef> for Catalog {
+    fn from_iter<T: IntoIterator<Item = DiagnosticDef>>(iter: T) -> Self {
+        Self {
+            defs: iter.into_iter().map(|def| (def.code, def)).collect(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Long explanation of a diagnostic
+///
+/// The text is written in a small subset of markdown: lines starting with `#` are headings
+/// and code examples are fenced with three backticks, everything else is displayed as it is.
+pub struct Explanation<'a> {
+    pub(crate) code: &'a str,
+    pub(crate) text: &'a str,
+    pub(crate) config: Config,
+    pub(crate) locale: &'a dyn Locale,
+}
+
+impl<'a> Explanation<'a> {
+    pub fn new(code: &'a str, text: &'a str) -> Self {
+        Self {
+            code,
+            text,
+            config: Config::default(),
+            locale: &English,
+        }
+    }
+
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+    }
+
+    pub fn with_locale(mut self, locale: &'a dyn Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn set_locale(&mut self, locale: &'a dyn Locale) {
+        self.locale = locale;
+    }
+
+    pub fn code(&self) -> &'a str {
+        self.code
+    }
+
+    pub fn write<B: Backend>(&self, backend: &mut B) -> Result<(), B::Error> {
+        backend.write_explanation(self)
+    }
+
+    pub(crate) fn blocks(&self) -> Vec<Block<'a>> {
+        let mut blocks = vec![];
+        let mut code: Option<Vec<&str>> = None;
+        for line in self.text.trim_matches('\n').lines() {
+            if line.trim_start().starts_with("```") {
+                match code.take() {
+                    Some(lines) => blocks.push(Block::Code(lines)),
+                    None => code = Some(vec![]),
+                }
+                continue;
+            }
+
+            if let Some(lines) = &mut code {
+                lines.push(line);
+            } else if line.starts_with('#') {
+                blocks.push(Block::Heading(line.trim_start_matches('#').trim()));
+            } else {
+                blocks.push(Block::Text(line));
+            }
+        }
+        // An unclosed code example continues until the end
+        blocks.extend(code.map(Block::Code));
+        blocks
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Block<'a> {
+    Heading(&'a str),
+    /// One line of text, empty lines separate paragraphs
+    Text(&'a str),
+    /// Lines of a code example
+    Code(Vec<&'a str>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A report could not be created from a [`DiagnosticDef`]
+pub struct TemplateError {
+    /// Code of the definition, empty if the code is not in the catalog
+    code: &'static str,
+    reason: TemplateErrorReason,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplateErrorReason {
+    UnknownCode(String),
+    MissingArgument(String),
+    UnmatchedBrace,
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.reason {
+            TemplateErrorReason::UnknownCode(code) => {
+                write!(f, "no diagnostic with code `{code}` in the catalog")
+            }
+            TemplateErrorReason::MissingArgument(name) => write!(
+                f,
+                "missing argument `{name}` for the message of diagnostic `{}`",
+                self.code
+            ),
+            TemplateErrorReason::UnmatchedBrace => write!(
+                f,
+                "unmatched brace in the message of diagnostic `{}`, use `{{{{` or `}}}}` for literal braces",
+                self.code
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[test]
+fn test_message_template() {
+    let def = DiagnosticDef::new(
+        "E0308",
+        ReportKind::Error,
+        "expected `{expected}`, found `{found}` {{in {expected}}}",
+    );
+    assert_eq!(
+        def.message(&[("found", &"Str"), ("expected", &"Nat"), ("unused", &1)])
+            .unwrap(),
+        "expected `Nat`, found `Str` {in Nat}"
+    );
+    assert_eq!(
+        def.message(&[("found", &"Str")]).unwrap_err().to_string(),
+        "missing argument `expected` for the message of diagnostic `E0308`"
+    );
+
+    let def = DiagnosticDef::new("W01", ReportKind::Warning, "unmatched { brace");
+    assert_eq!(
+        def.message(&[]).unwrap_err().reason,
+        TemplateErrorReason::UnmatchedBrace
+    );
+    let def = DiagnosticDef::new("W02", ReportKind::Warning, "unmatched } brace");
+    assert_eq!(
+        def.message(&[]).unwrap_err().reason,
+        TemplateErrorReason::UnmatchedBrace
+    );
+}
+
+#[test]
+fn test_explanation_blocks() {
+    let explanation = Explanation::new(
+        "E0412",
+        "\
+# Heading
+
+Text
+```rust
+let x: Nat = 1;
+```
+```
+unclosed",
+    );
+    assert_eq!(
+        explanation.blocks(),
+        [
+            Block::Heading("Heading"),
+            Block::Text(""),
+            Block::Text("Text"),
+            Block::Code(vec!["let x: Nat = 1;"]),
+            Block::Code(vec!["unclosed"]),
+        ]
+    );
+}
+
+#[test]
+fn test_explain_footer() {
+    let catalog = Catalog::from_iter([
+        DiagnosticDef::new("E0412", ReportKind::Error, "").with_explanation("..."),
+        DiagnosticDef::new("E0308", ReportKind::Error, "").with_explanation("..."),
+        DiagnosticDef::new("E0999", ReportKind::Error, ""),
+        DiagnosticDef::new("W01", ReportKind::Warning, "").with_explanation("..."),
+        DiagnosticDef::new("W02", ReportKind::Warning, "").with_explanation("..."),
+    ])
+    .with_explain_command("rustc --explain");
+
+    assert!(catalog
+        .explain_footer::<&str>(["E0999"], &English)
+        .is_none());
+    let footer = catalog
+        .explain_footer::<&str>(["E0412", "E0999", "E0412"], &English)
+        .unwrap();
+    assert!(footer.comments.is_empty());
+    let footer = catalog
+        .explain_footer::<&str>(["E0412", "E0308"], &English)
+        .unwrap();
+    assert_eq!(footer.comments.len(), 1);
+
+    // The wording follows the kinds of the explained codes
+    let message = |codes: &[&str]| {
+        let footer = catalog.explain_footer::<&str>(codes.iter().copied(), &English);
+        footer.unwrap().message[0].inner().to_string()
+    };
+    assert_eq!(
+        message(&["W01", "W02"]),
+        "Some warnings have detailed explanations: W01, W02."
+    );
+    assert_eq!(
+        message(&["E0412", "W01"]),
+        "Some diagnostics have detailed explanations: E0412, W01."
+    );
+    assert_eq!(
+        message(&["W01"]),
+        "For more information about this warning, try `rustc --explain W01`."
+    );
+}
+
+#[test]
+fn test_catalog() {
+    let catalog = Catalog::from_iter([
+        DiagnosticDef::new("E0412", ReportKind::Error, "cannot find type `{name}`"),
+        DiagnosticDef::new("E0308", ReportKind::Error, "mismatched types")
+            .with_explanation("Expected type did not match the received type."),
+    ]);
+
+    assert_eq!(
+        catalog.defs().map(DiagnosticDef::code).collect::<Vec<_>>(),
+        ["E0308", "E0412"]
+    );
+    assert_eq!(catalog.get("E0412").unwrap().explanation(), None);
+    assert!(catalog.report::<&str>("E0412", &[("name", &"Nat")]).is_ok());
+    assert_eq!(
+        catalog
+            .report::<&str>("E9999", &[])
+            .err()
+            .unwrap()
+            .to_string(),
+        "no diagnostic with code `E9999` in the catalog"
+    );
+}
@@ -0,0 +1,631 @@
+use crate::Style;
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    /// No color is set, the color of the parent element or the terminal is used
+    #[default]
+    Unset,
+    /// The default color of the terminal
+    Default,
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// Index into the 256 color palette
+    Fixed(u8),
+    Rgb(u8, u8, u8),
+}
+
+/// Named colors, ordered by their index in the 256 color palette
+const NAMED_COLORS: [(Color, &str); 16] = [
+    (Color::Black, "black"),
+    (Color::Red, "red"),
+    (Color::Green, "green"),
+    (Color::Yellow, "yellow"),
+    (Color::Blue, "blue"),
+    (Color::Magenta, "magenta"),
+    (Color::Cyan, "cyan"),
+    (Color::White, "white"),
+    (Color::BrightBlack, "bright-black"),
+    (Color::BrightRed, "bright-red"),
+    (Color::BrightGreen, "bright-green"),
+    (Color::BrightYellow, "bright-yellow"),
+    (Color::BrightBlue, "bright-blue"),
+    (Color::BrightMagenta, "bright-magenta"),
+    (Color::BrightCyan, "bright-cyan"),
+    (Color::BrightWhite, "bright-white"),
+];
+
+impl Color {
+    /// Index of named colors in the 256 color palette
+    pub(crate) fn named_index(self) -> Option<u8> {
+        NAMED_COLORS
+            .iter()
+            .position(|(color, _)| *color == self)
+            .map(|index| index as u8)
+    }
+
+    fn from_named_index(index: u8) -> Self {
+        NAMED_COLORS[index as usize].0
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Color::Unset => f.write_str("unset"),
+            Color::Default => f.write_str("default"),
+            Color::Fixed(index) => write!(f, "{index}"),
+            Color::Rgb(r, g, b) => write!(f, "#{r:02x}{g:02x}{b:02x}"),
+            named => f.write_str(NAMED_COLORS[named.named_index().unwrap() as usize].1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseColorError {
+    input: String,
+}
+
+impl fmt::Display for ParseColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid color `{}`, expected a color name like `bright-red`, \
+            a number from 0 to 255 or a hex code like `#ff8800`",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+/// Serialized in the format written by [`Display`](fmt::Display), like `"bright-red"` or `"#ff8800"`
+#[cfg(feature = "serde")]
+impl serde::Serialize for Color {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Color {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = std::borrow::Cow::<str>::deserialize(deserializer)?;
+        input.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    /// Parses the format written by [`Display`](fmt::Display),
+    /// names are case insensitive and can also be separated by `_` or spaces,
+    /// hex codes can also be written in their short form `#f80`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let error = || ParseColorError {
+            input: input.into(),
+        };
+        let name = input.trim().to_lowercase().replace(['_', ' '], "-");
+
+        if let Some(hex) = name.strip_prefix('#') {
+            let channel = |digits: &str| u8::from_str_radix(digits, 16).map_err(|_| error());
+            // `from_str_radix` would also accept signs like in `#+f+f+f`
+            if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+                return Err(error());
+            }
+            return match hex.len() {
+                3 => Ok(Color::Rgb(
+                    channel(&hex[0..1])? * 17,
+                    channel(&hex[1..2])? * 17,
+                    channel(&hex[2..3])? * 17,
+                )),
+                6 => Ok(Color::Rgb(
+                    channel(&hex[0..2])?,
+                    channel(&hex[2..4])?,
+                    channel(&hex[4..6])?,
+                )),
+                _ => Err(error()),
+            };
+        }
+
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            return name.parse().map(Color::Fixed).map_err(|_| error());
+        }
+
+        match name.as_str() {
+            "unset" | "none" => Ok(Color::Unset),
+            "default" => Ok(Color::Default),
+            name => NAMED_COLORS
+                .iter()
+                .find(|(_, color_name)| *color_name == name)
+                .map(|(color, _)| *color)
+                .ok_or_else(error),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// How many colors a terminal is able to display
+pub enum ColorDepth {
+    /// 24 bit RGB colors, colors are used as they are
+    #[default]
+    TrueColor,
+    /// The 256 color xterm palette
+    Ansi256,
+    /// The basic terminal colors
+    Ansi16,
+    /// No colors at all, other attributes like bold are still used
+    None,
+}
+
+impl ColorDepth {
+    /// Detects the color depth of the terminal from the environment variables
+    /// `NO_COLOR`, `CLICOLOR_FORCE`, `COLORTERM` and `TERM`.
+    pub fn detect() -> Self {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return ColorDepth::None;
+        }
+
+        let colorterm = var("COLORTERM").unwrap_or_default();
+        let term = var("TERM").unwrap_or_default();
+
+        if colorterm == "truecolor" || colorterm == "24bit" || term.ends_with("-direct") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else if (term.is_empty() || term == "dumb") && !is_forced(&var) {
+            ColorDepth::None
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// Replaces all colors of the style with the nearest supported color
+    pub fn downgrade_style(self, style: Style) -> Style {
+        style
+            .fg(self.downgrade(style.foreground))
+            .bg(self.downgrade(style.background))
+            .underline_color(self.downgrade(style.underline_color))
+    }
+
+    /// Returns the nearest color that can be displayed with this color depth
+    pub fn downgrade(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::TrueColor, color) => color,
+            (ColorDepth::None, _) => Color::Unset,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Fixed(nearest_fixed((r, g, b))),
+            (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => {
+                Color::from_named_index(nearest_basic((r, g, b)))
+            }
+            (ColorDepth::Ansi16, Color::Fixed(index)) => {
+                Color::from_named_index(nearest_basic(fixed_to_rgb(index)))
+            }
+            (_, color) => color,
+        }
+    }
+}
+
+fn is_forced(var: impl Fn(&str) -> Option<String>) -> bool {
+    var("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// Whether colors and other styles should be written
+pub enum ColorChoice {
+    /// Always use colors, even if `NO_COLOR` is set
+    Always,
+    /// Never use colors
+    Never,
+    /// Use colors if writing to a terminal and `NO_COLOR` is not set,
+    /// `CLICOLOR_FORCE` enables colors when not writing to a terminal.
+    #[default]
+    Auto,
+}
+
+impl ColorChoice {
+    /// Returns the color depth to use, [`ColorDepth::None`] means no styles should be written at all
+    pub fn color_depth(self, is_terminal: bool) -> ColorDepth {
+        self.color_depth_from_env(is_terminal, |name| std::env::var(name).ok())
+    }
+
+    fn color_depth_from_env(
+        self,
+        is_terminal: bool,
+        var: impl Fn(&str) -> Option<String>,
+    ) -> ColorDepth {
+        match self {
+            ColorChoice::Never => ColorDepth::None,
+            ColorChoice::Auto if !is_terminal && !is_forced(&var) => ColorDepth::None,
+            ColorChoice::Auto => ColorDepth::from_env(var),
+            ColorChoice::Always => match ColorDepth::from_env(|name| {
+                // Only use the environment to find out how many colors are supported
+                (name != "NO_COLOR").then(|| var(name)).flatten()
+            }) {
+                ColorDepth::None => ColorDepth::Ansi16,
+                color_depth => color_depth,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Generates visually distinct colors for labels
+///
+/// The first colors are taken from the Okabe-Ito palette, which stays distinguishable
+/// for the common forms of color blindness. After all of them have been used once,
+/// they are repeated with shifted hues in alternating lighter and darker shades.
+/// The seed decides the order of the palette, the hue shifts and the shades.
+/// A color is never generated twice.
+pub struct ColorGenerator {
+    seed: u64,
+    /// Number of candidate colors that were already considered,
+    /// not serialized so deserialized generators start with the first color
+    #[cfg_attr(feature = "serde", serde(skip))]
+    index: usize,
+}
+
+impl ColorGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The same seed always generates the same colors in the same order
+    pub fn from_seed(seed: u64) -> Self {
+        Self { seed, index: 0 }
+    }
+
+    /// Candidate color at the index, candidates may repeat earlier ones
+    fn candidate(&self, index: usize) -> Color {
+        let len = DISTINCT_COLORS.len();
+        let round = index / len;
+
+        // Each round uses its own order of the palette
+        let mut order: [usize; 7] = std::array::from_fn(|index| index);
+        let mut random = SplitMix64(self.seed ^ round as u64);
+        for index in (1..len).rev() {
+            order.swap(index, (random.next() % (index as u64 + 1)) as usize);
+        }
+        let (r, g, b) = DISTINCT_COLORS[order[index % len]];
+        if round == 0 {
+            return Color::Rgb(r, g, b);
+        }
+
+        // Hues are shifted by less than the distance between the palette colors,
+        // every second round is lighter, the others are darker
+        let (hue, saturation, lightness) = rgb_to_hsl(r, g, b);
+        let shift = 15.0 + (random.next() % 20) as f64;
+        let hue = (hue + shift * round as f64) % 360.0;
+        let step = 0.08 + (random.next() % 5) as f64 / 100.0;
+        let change = step * round.div_ceil(2) as f64;
+        let lightness = match round % 2 {
+            1 => lightness + change,
+            _ => lightness - change,
+        };
+        // Reflected at the bounds, so shades do not end up at white or black
+        let (min, max) = (0.25, 0.8);
+        let offset = (lightness - min).rem_euclid(2.0 * (max - min));
+        let lightness = min + offset.min(2.0 * (max - min) - offset);
+        let (r, g, b) = hsl_to_rgb(hue, saturation, lightness);
+        Color::Rgb(r, g, b)
+    }
+}
+
+/// Okabe-Ito palette without black
+const DISTINCT_COLORS: [(u8, u8, u8); 7] = [
+    (230, 159, 0),
+    (86, 180, 233),
+    (0, 158, 115),
+    (240, 228, 66),
+    (0, 114, 178),
+    (213, 94, 0),
+    (204, 121, 167),
+];
+
+impl Iterator for ColorGenerator {
+    type Item = Color;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let color = self.candidate(self.index);
+            self.index += 1;
+            if (0..self.index - 1).all(|index| self.candidate(index) != color) {
+                return Some(color);
+            }
+        }
+    }
+}
+
+/// Small deterministic random number generator, stable across versions unlike the standard library
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Hue in degrees, saturation and lightness from 0 to 1
+fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f64, f64, f64) {
+    let [r, g, b] = [r, g, b].map(|channel| channel as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = match max {
+        max if max == r => 60.0 * ((g - b) / delta).rem_euclid(6.0),
+        max if max == g => 60.0 * ((b - r) / delta + 2.0),
+        _ => 60.0 * ((r - g) / delta + 4.0),
+    };
+    (hue, saturation, lightness)
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (u8, u8, u8) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |value: f64| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+/// The basic colors as they are displayed by xterm
+const BASIC_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Levels of each channel of the 6x6x6 color cube
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn fixed_to_rgb(index: u8) -> (u8, u8, u8) {
+    match index {
+        0..=15 => BASIC_COLORS[index as usize],
+        16..=231 => {
+            let index = index - 16;
+            (
+                CUBE_LEVELS[(index / 36) as usize],
+                CUBE_LEVELS[(index / 6 % 6) as usize],
+                CUBE_LEVELS[(index % 6) as usize],
+            )
+        }
+        232..=255 => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    let diff = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+    diff(r1, r2) + diff(g1, g2) + diff(b1, b2)
+}
+
+/// Nearest color of the 256 color palette, the user configurable basic colors are not considered
+fn nearest_fixed(rgb: (u8, u8, u8)) -> u8 {
+    (16..=255).fold(16, |nearest, index| {
+        match distance(rgb, fixed_to_rgb(index)) < distance(rgb, fixed_to_rgb(nearest)) {
+            true => index,
+            false => nearest,
+        }
+    })
+}
+
+/// Index of the nearest basic color
+fn nearest_basic(rgb: (u8, u8, u8)) -> u8 {
+    (0..16).fold(0, |nearest, index| {
+        match distance(rgb, BASIC_COLORS[index as usize])
+            < distance(rgb, BASIC_COLORS[nearest as usize])
+        {
+            true => index,
+            false => nearest,
+        }
+    })
+}
+
+#[test]
+fn test_detect() {
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        ColorDepth::from_env(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        })
+    };
+
+    assert_eq!(env(&[]), ColorDepth::None);
+    assert_eq!(env(&[("TERM", "dumb")]), ColorDepth::None);
+    assert_eq!(env(&[("TERM", "xterm")]), ColorDepth::Ansi16);
+    assert_eq!(env(&[("TERM", "xterm-256color")]), ColorDepth::Ansi256);
+    assert_eq!(
+        env(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]),
+        ColorDepth::TrueColor
+    );
+    assert_eq!(
+        env(&[("TERM", "xterm-256color"), ("NO_COLOR", "1")]),
+        ColorDepth::None
+    );
+    assert_eq!(
+        env(&[("TERM", "xterm"), ("NO_COLOR", "")]),
+        ColorDepth::Ansi16
+    );
+    assert_eq!(
+        env(&[("TERM", "dumb"), ("CLICOLOR_FORCE", "1")]),
+        ColorDepth::Ansi16
+    );
+    assert_eq!(
+        env(&[("TERM", "dumb"), ("CLICOLOR_FORCE", "0")]),
+        ColorDepth::None
+    );
+}
+
+#[test]
+fn test_color_choice() {
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    };
+    let xterm = env(&[("TERM", "xterm-256color")]);
+    let no_color = env(&[("TERM", "xterm-256color"), ("NO_COLOR", "1")]);
+    let forced = env(&[("TERM", "xterm"), ("CLICOLOR_FORCE", "1")]);
+
+    use ColorChoice::*;
+    assert_eq!(Auto.color_depth_from_env(true, xterm), ColorDepth::Ansi256);
+    assert_eq!(Auto.color_depth_from_env(false, xterm), ColorDepth::None);
+    assert_eq!(Auto.color_depth_from_env(true, no_color), ColorDepth::None);
+    assert_eq!(Auto.color_depth_from_env(false, forced), ColorDepth::Ansi16);
+    assert_eq!(
+        Always.color_depth_from_env(false, xterm),
+        ColorDepth::Ansi256
+    );
+    assert_eq!(
+        Always.color_depth_from_env(false, no_color),
+        ColorDepth::Ansi256
+    );
+    assert_eq!(
+        Always.color_depth_from_env(false, env(&[])),
+        ColorDepth::Ansi16
+    );
+    assert_eq!(Never.color_depth_from_env(true, xterm), ColorDepth::None);
+}
+
+#[test]
+fn test_downgrade() {
+    let orange = Color::Rgb(255, 135, 0);
+    assert_eq!(ColorDepth::TrueColor.downgrade(orange), orange);
+    assert_eq!(ColorDepth::Ansi256.downgrade(orange), Color::Fixed(208));
+    assert_eq!(ColorDepth::Ansi16.downgrade(orange), Color::Yellow);
+    assert_eq!(ColorDepth::None.downgrade(orange), Color::Unset);
+
+    assert_eq!(
+        ColorDepth::Ansi256.downgrade(Color::Rgb(128, 128, 128)),
+        Color::Fixed(244)
+    );
+    assert_eq!(
+        ColorDepth::Ansi256.downgrade(Color::Fixed(9)),
+        Color::Fixed(9)
+    );
+    assert_eq!(
+        ColorDepth::Ansi16.downgrade(Color::Fixed(9)),
+        Color::BrightRed
+    );
+    assert_eq!(ColorDepth::Ansi16.downgrade(Color::Fixed(21)), Color::Blue);
+    assert_eq!(ColorDepth::Ansi16.downgrade(Color::Cyan), Color::Cyan);
+    assert_eq!(ColorDepth::None.downgrade(Color::Cyan), Color::Unset);
+}
+
+#[test]
+fn test_color_generator() {
+    for seed in 0..20 {
+        let colors = ColorGenerator::from_seed(seed)
+            .take(100)
+            .collect::<Vec<_>>();
+        for (index, color) in colors.iter().enumerate() {
+            assert!(!colors[..index].contains(color), "{color:?} is repeated");
+        }
+        // The palette comes first
+        for (r, g, b) in DISTINCT_COLORS {
+            assert!(colors[..7].contains(&Color::Rgb(r, g, b)));
+        }
+    }
+
+    assert_eq!(
+        ColorGenerator::from_seed(42).take(10).collect::<Vec<_>>(),
+        ColorGenerator::from_seed(42).take(10).collect::<Vec<_>>()
+    );
+    // Seeds change more than the order of the palette
+    assert_ne!(
+        ColorGenerator::from_seed(1)
+            .skip(7)
+            .take(7)
+            .collect::<Vec<_>>(),
+        ColorGenerator::from_seed(8)
+            .skip(7)
+            .take(7)
+            .collect::<Vec<_>>()
+    );
+    for (r, g, b) in DISTINCT_COLORS {
+        let (hue, saturation, lightness) = rgb_to_hsl(r, g, b);
+        assert_eq!(hsl_to_rgb(hue, saturation, lightness), (r, g, b));
+    }
+}
+
+#[test]
+fn test_parse_color() {
+    assert_eq!("#ff8800".parse(), Ok(Color::Rgb(255, 136, 0)));
+    assert_eq!("#F80".parse(), Ok(Color::Rgb(255, 136, 0)));
+    assert_eq!("bright-red".parse(), Ok(Color::BrightRed));
+    assert_eq!("Bright_Red".parse(), Ok(Color::BrightRed));
+    assert_eq!("cyan".parse(), Ok(Color::Cyan));
+    assert_eq!("208".parse(), Ok(Color::Fixed(208)));
+    assert_eq!("default".parse(), Ok(Color::Default));
+    assert_eq!("unset".parse(), Ok(Color::Unset));
+
+    for invalid in [
+        "", "256", "-1", "#ff88", "#gg8800", "#ä", "orange", "#+f+f+f", "#+ff",
+    ] {
+        assert!(invalid.parse::<Color>().is_err(), "{invalid:?} was parsed");
+    }
+
+    for color in [
+        Color::Unset,
+        Color::Default,
+        Color::Magenta,
+        Color::BrightWhite,
+        Color::Fixed(42),
+        Color::Rgb(1, 22, 255),
+    ] {
+        assert_eq!(color.to_string().parse(), Ok(color));
+    }
+}
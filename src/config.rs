@@ -0,0 +1,98 @@
+use crate::{Color, Style, Theme, Underline};
+
+#[derive(Debug, Clone, Copy)]
+/// Options that control how a [`Report`](crate::Report) is laid out
+pub struct Config {
+    /// Number of columns a tab character is expanded to
+    pub(crate) tab_width: usize,
+    /// Whether to add a note when labeled source code contains invisible characters
+    pub(crate) invisible_chars_note: bool,
+    /// Whether whitespace inside of labeled spans is displayed as visible glyphs
+    pub(crate) visible_whitespace: bool,
+    /// How the labeled source code itself is highlighted
+    pub(crate) label_highlight: LabelHighlight,
+    pub(crate) theme: Theme,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            tab_width: 4,
+            invisible_chars_note: false,
+            visible_whitespace: false,
+            label_highlight: LabelHighlight::None,
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tab_width(mut self, tab_width: usize) -> Self {
+        self.tab_width = tab_width;
+        self
+    }
+
+    /// Control, bidi override and zero width characters are always displayed as visible escapes,
+    /// this additionally explains the escapes found inside of labeled spans in a note.
+    pub fn with_invisible_chars_note(mut self, enabled: bool) -> Self {
+        self.invisible_chars_note = enabled;
+        self
+    }
+
+    /// Display spaces (`·`), tabs (`→`) and non-breaking spaces (`⍽`) inside of labeled spans
+    /// as visible glyphs in the color of the label.
+    ///
+    /// Can be overwritten for each label using [`Label::with_visible_whitespace`](crate::Label::with_visible_whitespace).
+    pub fn with_visible_whitespace(mut self, enabled: bool) -> Self {
+        self.visible_whitespace = enabled;
+        self
+    }
+
+    /// Highlight the source code covered by each label with the color of the label,
+    /// similar to a selection in a text editor.
+    pub fn with_label_highlight(mut self, label_highlight: LabelHighlight) -> Self {
+        self.label_highlight = label_highlight;
+        self
+    }
+
+    /// Styles and characters used to display the report
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// How the color of a [`Label`](crate::Label) is applied to the source code it covers
+pub enum LabelHighlight {
+    /// The labeled source code is not highlighted
+    #[default]
+    None,
+    /// Use the color of the label for the text
+    Foreground,
+    /// Use the color of the label for the background
+    Background,
+    /// Underline the text using the color of the label
+    Underline(Underline),
+}
+
+impl LabelHighlight {
+    pub(crate) fn apply(self, style: Style, color: Color) -> Style {
+        match self {
+            LabelHighlight::None => style,
+            // Keep colors from syntax highlighting for labels without a color
+            LabelHighlight::Foreground | LabelHighlight::Background if color == Color::Unset => {
+                style
+            }
+            LabelHighlight::Foreground => style.fg(color),
+            LabelHighlight::Background => style.bg(color),
+            LabelHighlight::Underline(underline) => {
+                style.underline_kind(underline).underline_color(color)
+            }
+        }
+    }
+}
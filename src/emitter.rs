@@ -0,0 +1,754 @@
+use crate::{
+    Backend, Baseline, Cache, Catalog, ColorGenerator, English, Label, LintLevels, Locale, Report,
+    ReportKind, Styled, StyledStr,
+};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroUsize;
+use std::process::ExitCode;
+
+/// Writes reports to a [`Backend`] and keeps track of what was written
+///
+/// Counts the reports of each kind, can turn warnings into errors, stop after too many errors
+/// and writes a summary like "could not compile `crate` due to 2 previous errors"
+/// once [`Emitter::finish`] is called.
+///
+/// Identical reports are only written once, reports with the same code
+/// can be collapsed into a note like "and 15 more similar warnings".
+pub struct Emitter<'a, B: Backend, C> {
+    backend: B,
+    cache: C,
+    counts: Counts,
+    /// Codes of the emitted reports, in the order they were seen
+    codes: Vec<String>,
+    deduplicate: bool,
+    /// Content of the written reports
+    seen: HashSet<ReportKey>,
+    similar_limit: Option<usize>,
+    /// Number of written reports per code
+    similar: HashMap<String, usize>,
+    /// Reports that were not written because of the similar limit, per code and kind
+    hidden: Vec<(String, ReportKind, usize)>,
+    lint_levels: LintLevels,
+    /// Reports in the baseline are not written, each occurrence is removed once it hid a report
+    baseline: Option<Baseline>,
+    /// Fingerprints of the emitted reports, including the ones in the baseline
+    fingerprints: Baseline,
+    /// Whether fingerprints are collected without a baseline
+    collect_fingerprints: bool,
+    deny_warnings: bool,
+    max_errors: Option<NonZeroUsize>,
+    aborted: bool,
+    /// Whether [`Emitter::finish`] was called
+    finished: bool,
+    summary: bool,
+    summary_subject: Option<String>,
+    catalog: Option<&'a Catalog>,
+    locale: &'a dyn Locale,
+}
+
+impl<'a, B: Backend, C> Emitter<'a, B, C> {
+    pub fn new(backend: B, cache: C) -> Self {
+        Self {
+            backend,
+            cache,
+            counts: Counts::default(),
+            codes: vec![],
+            deduplicate: true,
+            seen: HashSet::new(),
+            similar_limit: None,
+            similar: HashMap::new(),
+            hidden: vec![],
+            lint_levels: LintLevels::default(),
+            baseline: None,
+            fingerprints: Baseline::default(),
+            collect_fingerprints: false,
+            deny_warnings: false,
+            max_errors: None,
+            aborted: false,
+            finished: false,
+            summary: true,
+            summary_subject: None,
+            catalog: None,
+            locale: &English,
+        }
+    }
+
+    /// Levels of warnings and errors by their code, applied before `--deny warnings`
+    pub fn with_lint_levels(mut self, lint_levels: LintLevels) -> Self {
+        self.lint_levels = lint_levels;
+        self
+    }
+
+    pub fn set_lint_levels(&mut self, lint_levels: LintLevels) {
+        self.lint_levels = lint_levels;
+    }
+
+    /// Only writes reports that are not in the baseline, known reports are not counted either.
+    /// Every occurrence of a fingerprint in the baseline hides one report.
+    pub fn with_baseline(mut self, baseline: Baseline) -> Self {
+        self.baseline = Some(baseline);
+        self
+    }
+
+    pub fn set_baseline(&mut self, baseline: Baseline) {
+        self.baseline = Some(baseline);
+    }
+
+    /// Collects the fingerprints of all emitted reports even without a baseline
+    pub fn with_fingerprints(mut self, enabled: bool) -> Self {
+        self.collect_fingerprints = enabled;
+        self
+    }
+
+    pub fn set_fingerprints(&mut self, enabled: bool) {
+        self.collect_fingerprints = enabled;
+    }
+
+    /// Fingerprints of all emitted reports, including the ones in the baseline,
+    /// which can be written as a new baseline.
+    /// Only collected with a baseline or [`Emitter::with_fingerprints`].
+    pub fn fingerprints(&self) -> &Baseline {
+        &self.fingerprints
+    }
+
+    /// Emits warnings as errors, like `--deny warnings`
+    pub fn with_deny_warnings(mut self, enabled: bool) -> Self {
+        self.deny_warnings = enabled;
+        self
+    }
+
+    pub fn set_deny_warnings(&mut self, enabled: bool) {
+        self.deny_warnings = enabled;
+    }
+
+    /// Stops emitting reports after the given number of errors, all further reports are dropped.
+    /// The emitter then writes "aborting after 2 errors", which replaces the summary of [`Emitter::finish`].
+    pub fn with_max_errors(mut self, max_errors: Option<NonZeroUsize>) -> Self {
+        self.max_errors = max_errors;
+        self
+    }
+
+    pub fn set_max_errors(&mut self, max_errors: Option<NonZeroUsize>) {
+        self.max_errors = max_errors;
+    }
+
+    /// Writes identical reports only once, enabled by default. See [`Report`] for what makes reports equal,
+    /// source ids are compared by their [display id](Cache::display_id).
+    pub fn with_deduplicate(mut self, enabled: bool) -> Self {
+        self.deduplicate = enabled;
+        self
+    }
+
+    pub fn set_deduplicate(&mut self, enabled: bool) {
+        self.deduplicate = enabled;
+    }
+
+    /// Writes at most this many reports with the same code,
+    /// the remaining ones are still counted and mentioned in a note by [`Emitter::finish`]
+    pub fn with_similar_limit(mut self, limit: Option<usize>) -> Self {
+        self.similar_limit = limit;
+        self
+    }
+
+    pub fn set_similar_limit(&mut self, limit: Option<usize>) {
+        self.similar_limit = limit;
+    }
+
+    /// Whether [`Emitter::finish`] writes a summary, enabled by default
+    pub fn with_summary(mut self, enabled: bool) -> Self {
+        self.summary = enabled;
+        self
+    }
+
+    pub fn set_summary(&mut self, enabled: bool) {
+        self.summary = enabled;
+    }
+
+    /// What failed or produced warnings, like "could not compile `crate` (lib)"
+    pub fn with_summary_subject(mut self, subject: impl Into<String>) -> Self {
+        self.summary_subject = Some(subject.into());
+        self
+    }
+
+    pub fn set_summary_subject(&mut self, subject: impl Into<String>) {
+        self.summary_subject = Some(subject.into());
+    }
+
+    /// Lists the emitted codes that have an explanation before the summary,
+    /// see [`Catalog::explain_footer`]
+    pub fn with_catalog(mut self, catalog: &'a Catalog) -> Self {
+        self.catalog = Some(catalog);
+        self
+    }
+
+    pub fn set_catalog(&mut self, catalog: &'a Catalog) {
+        self.catalog = Some(catalog);
+    }
+
+    /// Locale of the reports written by the emitter itself
+    pub fn with_locale(mut self, locale: &'a dyn Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn set_locale(&mut self, locale: &'a dyn Locale) {
+        self.locale = locale;
+    }
+
+    pub fn counts(&self) -> &Counts {
+        &self.counts
+    }
+
+    /// Whether the maximum number of errors was reached
+    pub fn is_aborted(&self) -> bool {
+        self.aborted
+    }
+
+    pub fn into_inner(self) -> (B, C) {
+        (self.backend, self.cache)
+    }
+
+    pub fn emit<SourceId>(&mut self, mut report: Report<'_, SourceId>) -> Result<(), B::Error>
+    where
+        C: Cache<SourceId>,
+    {
+        if self.aborted {
+            return Ok(());
+        }
+
+        let is_lint = matches!(report.kind, ReportKind::Warning | ReportKind::Error);
+        let level = (report.code.as_deref()).and_then(|code| self.lint_levels.level(code));
+        if let (true, Some((pattern, level))) = (is_lint, level) {
+            match level.kind() {
+                None => return Ok(()),
+                Some(kind) if kind != report.kind => {
+                    report.kind = kind;
+                    report.set_comment(ReportKind::Note, self.locale.lint_level(pattern, level));
+                }
+                Some(_) => {}
+            }
+        }
+
+        if self.collect_fingerprints || self.baseline.is_some() {
+            let fingerprint = report.fingerprint(&mut self.cache);
+            self.fingerprints.insert(fingerprint);
+            if (self.baseline.as_mut()).is_some_and(|baseline| baseline.remove(fingerprint)) {
+                return Ok(());
+            }
+        }
+
+        if self.deny_warnings && matches!(report.kind, ReportKind::Warning) {
+            report.kind = ReportKind::Error;
+            report.set_comment(ReportKind::Note, self.locale.denied_warning());
+        }
+
+        if self.deduplicate && !self.seen.insert(ReportKey::new(&report, &mut self.cache)) {
+            return Ok(());
+        }
+
+        self.counts.add(report.kind);
+        if let Some(code) = &report.code {
+            if !self.codes.contains(code) {
+                self.codes.push(code.clone());
+            }
+        }
+
+        if self.is_similar(&report) {
+            let code = report.code.clone().unwrap_or_default();
+            match (self.hidden.iter_mut())
+                .find(|(other, kind, _)| *other == code && *kind == report.kind)
+            {
+                Some((_, _, count)) => *count += 1,
+                None => self.hidden.push((code, report.kind, 1)),
+            }
+        } else {
+            self.backend.write(&report, &mut self.cache)?;
+        }
+
+        let errors = self.counts.errors();
+        let is_error = matches!(report.kind, ReportKind::Error);
+        if is_error && self.max_errors.is_some_and(|max| errors >= max.get()) {
+            self.aborted = true;
+            let message = self.locale.aborting(errors);
+            self.write_own(Report::new(ReportKind::Error).with_message(message))?;
+        }
+        Ok(())
+    }
+
+    /// Whether the similar limit was reached for the code of the report
+    fn is_similar<SourceId>(&mut self, report: &Report<'_, SourceId>) -> bool {
+        let (Some(limit), Some(code)) = (self.similar_limit, &report.code) else {
+            return false;
+        };
+        let written = self.similar.entry(code.clone()).or_default();
+        if *written >= limit {
+            return true;
+        }
+        *written += 1;
+        false
+    }
+
+    /// Writes the explanation footer and the summary and finishes the backend with [`Backend::finish`],
+    /// the returned counts recommend an exit code with [`Counts::exit_code`].
+    /// Later calls only return the counts.
+    pub fn finish(&mut self) -> Result<Counts, B::Error> {
+        if std::mem::replace(&mut self.finished, true) {
+            return Ok(self.counts.clone());
+        }
+
+        for (code, kind, count) in std::mem::take(&mut self.hidden) {
+            let message = self.locale.more_similar(kind, count);
+            self.write_own(
+                Report::new(ReportKind::Note)
+                    .with_code(code)
+                    .with_message(message),
+            )?;
+        }
+
+        if let Some(catalog) = self.catalog {
+            let codes = self.codes.iter().map(String::as_str);
+            if let Some(footer) = catalog.explain_footer(codes, self.locale) {
+                self.backend.write(&footer, &mut ())?;
+            }
+        }
+
+        if self.summary && !self.aborted {
+            let (errors, warnings) = (self.counts.errors(), self.counts.warnings());
+            let subject = self.summary_subject.as_deref();
+            if let Some(message) = self.locale.summary(subject, errors, warnings) {
+                let kind = match errors {
+                    0 => ReportKind::Warning,
+                    _ => ReportKind::Error,
+                };
+                self.write_own(Report::new(kind).with_message(message))?;
+            }
+        }
+
+        self.backend.finish()?;
+        Ok(self.counts.clone())
+    }
+
+    /// Writes a report created by the emitter, which is not counted
+    fn write_own(&mut self, report: Report<'_, ()>) -> Result<(), B::Error> {
+        self.backend
+            .write(&report.with_locale(self.locale), &mut ())
+    }
+}
+
+#[derive(PartialEq, Eq, Hash)]
+/// Owned copy of the content of a report, with source ids replaced by their display id
+struct ReportKey {
+    kind: ReportKind,
+    code: Option<String>,
+    url: Option<String>,
+    message: Vec<StyledStr<'static>>,
+    views: Vec<ViewKey>,
+    comments: Vec<(ReportKind, Vec<StyledStr<'static>>)>,
+}
+
+#[derive(PartialEq, Eq, Hash)]
+struct ViewKey {
+    source: Option<String>,
+    location: usize,
+    labels: Vec<Label<'static>>,
+    color_generator: Option<ColorGenerator>,
+}
+
+impl ReportKey {
+    fn new<SourceId>(report: &Report<'_, SourceId>, cache: &mut impl Cache<SourceId>) -> Self {
+        let views = (report.views.iter())
+            .map(|view| ViewKey {
+                source: (cache.display_id(&view.source_id)).map(|id| id.to_string()),
+                location: view.location,
+                labels: (view.labels.iter())
+                    .map(|label| Label {
+                        span: label.span.clone(),
+                        message: label.message.as_deref().map(owned),
+                        color: label.color,
+                        visible_whitespace: label.visible_whitespace,
+                    })
+                    .collect(),
+                color_generator: view.color_generator.clone(),
+            })
+            .collect();
+        Self {
+            kind: report.kind,
+            code: report.code.clone(),
+            url: report.url.clone(),
+            message: owned(&report.message),
+            views,
+            comments: (report.comments.iter())
+                .map(|(kind, comment)| (*kind, owned(comment)))
+                .collect(),
+        }
+    }
+}
+
+fn owned(text: &[StyledStr]) -> Vec<StyledStr<'static>> {
+    text.iter()
+        .map(|part| Styled::new(Cow::Owned(part.inner().to_string()), *part.style()))
+        .collect()
+}
+
+/// Collects reports and emits them sorted, so the output reads top-to-bottom through each source
+///
+/// Reports are sorted by their source, the location of their first [`SourceView`](crate::SourceView)
+/// and their kind, with errors before warnings, notes, help and custom kinds.
+/// Sources are ordered by their id, reports without views come last.
+/// Otherwise reports keep the order they were added in.
+pub struct ReportBuffer<'a, SourceId> {
+    reports: Vec<Report<'a, SourceId>>,
+}
+
+impl<SourceId> Default for ReportBuffer<'_, SourceId> {
+    fn default() -> Self {
+        Self { reports: vec![] }
+    }
+}
+
+impl<'a, SourceId: Ord> ReportBuffer<'a, SourceId> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_report(&mut self, report: Report<'a, SourceId>) {
+        self.reports.push(report);
+    }
+
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    pub fn into_sorted(self) -> Vec<Report<'a, SourceId>> {
+        let mut reports = self.reports;
+        reports.sort_by(|a, b| sort_key(a).cmp(&sort_key(b)));
+        reports
+    }
+
+    /// Emits all reports in sorted order
+    pub fn flush<B: Backend, C: Cache<SourceId>>(
+        self,
+        emitter: &mut Emitter<'_, B, C>,
+    ) -> Result<(), B::Error> {
+        for report in self.into_sorted() {
+            emitter.emit(report)?;
+        }
+        Ok(())
+    }
+}
+
+fn sort_key<'r, SourceId>(
+    report: &'r Report<SourceId>,
+) -> (bool, Option<(&'r SourceId, usize)>, u8) {
+    let location = (report.views.first()).map(|view| (&view.source_id, view.location));
+    // `None` is sorted before `Some`, so reports without views are moved to the end
+    (location.is_none(), location, severity(report.kind))
+}
+
+fn severity(kind: ReportKind) -> u8 {
+    match kind {
+        ReportKind::Error => 0,
+        ReportKind::Warning => 1,
+        ReportKind::Note => 2,
+        ReportKind::Help => 3,
+        ReportKind::Custom(..) => 4,
+    }
+}
+
+impl<'a, SourceId> Extend<Report<'a, SourceId>> for ReportBuffer<'a, SourceId> {
+    fn extend<T: IntoIterator<Item = Report<'a, SourceId>>>(&mut self, iter: T) {
+        self.reports.extend(iter);
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Number of emitted reports per kind
+pub struct Counts {
+    counts: Vec<(ReportKind, usize)>,
+}
+
+impl Counts {
+    fn add(&mut self, kind: ReportKind) {
+        match self.counts.iter_mut().find(|(other, _)| *other == kind) {
+            Some((_, count)) => *count += 1,
+            None => self.counts.push((kind, 1)),
+        }
+    }
+
+    pub fn count(&self, kind: ReportKind) -> usize {
+        self.counts
+            .iter()
+            .find(|(other, _)| *other == kind)
+            .map_or(0, |(_, count)| *count)
+    }
+
+    pub fn errors(&self) -> usize {
+        self.count(ReportKind::Error)
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.count(ReportKind::Warning)
+    }
+
+    /// Counts of all kinds that were emitted, in the order they were first seen
+    pub fn iter(&self) -> impl Iterator<Item = (ReportKind, usize)> + '_ {
+        self.counts.iter().copied()
+    }
+
+    /// Failure if any error was emitted
+    pub fn exit_code(&self) -> ExitCode {
+        match self.errors() {
+            0 => ExitCode::SUCCESS,
+            _ => ExitCode::FAILURE,
+        }
+    }
+}
+
+#[test]
+fn test_emitter() {
+    use crate::PlainText;
+
+    let mut output = Vec::new();
+    let mut emitter = Emitter::new(PlainText(&mut output), ())
+        .with_deny_warnings(true)
+        .with_max_errors(NonZeroUsize::new(2));
+    emitter
+        .emit(Report::new(ReportKind::Help).with_message("first"))
+        .unwrap();
+    emitter
+        .emit(Report::new(ReportKind::Warning).with_message("second"))
+        .unwrap();
+    emitter
+        .emit(Report::new(ReportKind::Error).with_message("third"))
+        .unwrap();
+    assert!(emitter.is_aborted());
+    emitter
+        .emit(Report::new(ReportKind::Error).with_message("dropped"))
+        .unwrap();
+    let counts = emitter.finish().unwrap();
+
+    assert_eq!(counts.errors(), 2);
+    assert_eq!(counts.warnings(), 0);
+    assert_eq!(counts.count(ReportKind::Help), 1);
+    assert_eq!(counts.exit_code(), ExitCode::FAILURE);
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "\
+Help: first
+Error: second
+Note: warnings are denied and reported as errors
+Error: third
+Error: aborting after 2 errors
+"
+    );
+}
+
+#[test]
+fn test_finish_twice() {
+    use crate::PlainText;
+
+    let mut output = Vec::new();
+    let mut emitter = Emitter::new(PlainText(&mut output), ());
+    emitter
+        .emit(Report::new(ReportKind::Warning).with_message("unused"))
+        .unwrap();
+    let counts = emitter.finish().unwrap();
+    assert_eq!(emitter.finish().unwrap(), counts);
+
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Warning: unused\nWarning: 1 warning emitted\n"
+    );
+}
+
+#[test]
+fn test_deduplicate() {
+    use crate::{Label, PlainText, SourceView};
+
+    let report = |code, span| {
+        Report::new(ReportKind::Warning)
+            .with_code(code)
+            .with_message("unused variable")
+            .with_view(SourceView::new("main.rs", 0).with_label(Label::new(span)))
+    };
+
+    let mut output = Vec::new();
+    let mut emitter = Emitter::new(PlainText(&mut output), vec![("main.rs", "let x = 1;")])
+        .with_similar_limit(Some(1))
+        .with_summary(false);
+    emitter.emit(report("W1", 4..5)).unwrap();
+    // Identical reports are dropped without being counted
+    emitter.emit(report("W1", 4..5)).unwrap();
+    emitter.emit(report("W1", 0..3)).unwrap();
+    emitter.emit(report("W1", 8..9)).unwrap();
+    emitter.emit(report("W2", 4..5)).unwrap();
+    let counts = emitter.finish().unwrap();
+
+    assert_eq!(counts.warnings(), 4);
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.matches("Warning: unused variable").count(), 2);
+    assert!(output.ends_with("[W1] Note: and 2 more similar warnings\n"));
+}
+
+#[test]
+fn test_report_buffer() {
+    use crate::SourceView;
+
+    let report = |kind, message, view: Option<(&'static str, usize)>| {
+        let mut report = Report::new(kind).with_message(message);
+        if let Some((id, location)) = view {
+            report.add_view(SourceView::new(id, location));
+        }
+        report
+    };
+    let reports = || {
+        [
+            report(ReportKind::Warning, "b.rs 10", Some(("b.rs", 10))),
+            report(ReportKind::Error, "no view", None),
+            report(ReportKind::Warning, "a.rs 5", Some(("a.rs", 5))),
+            report(ReportKind::Help, "b.rs 2 help", Some(("b.rs", 2))),
+            report(ReportKind::Error, "a.rs 5 error", Some(("a.rs", 5))),
+            report(ReportKind::Warning, "b.rs 2 warning", Some(("b.rs", 2))),
+        ]
+    };
+    let sorted = |buffer: ReportBuffer<'static, &'static str>| {
+        (buffer.into_sorted().iter())
+            .map(|report| report.message[0].inner().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let mut buffer = ReportBuffer::new();
+    buffer.extend(reports());
+    assert_eq!(buffer.len(), 6);
+    let messages = sorted(buffer);
+    assert_eq!(
+        messages,
+        [
+            "a.rs 5 error",
+            "a.rs 5",
+            "b.rs 2 warning",
+            "b.rs 2 help",
+            "b.rs 10",
+            "no view"
+        ]
+    );
+
+    // The order the reports are added in does not matter
+    let mut shuffled = reports();
+    shuffled.reverse();
+    shuffled.rotate_left(2);
+    let mut buffer = ReportBuffer::new();
+    buffer.extend(shuffled);
+    assert_eq!(sorted(buffer), messages);
+}
+
+#[test]
+fn test_lint_levels() {
+    use crate::{LintLevel, PlainText};
+
+    let mut output = Vec::new();
+    let mut emitter = Emitter::new(PlainText(&mut output), ())
+        .with_lint_levels(
+            LintLevels::new()
+                .with_level("style::*", LintLevel::Deny)
+                .with_level("style::needless_return", LintLevel::Allow)
+                .with_level("E0412", LintLevel::Warn),
+        )
+        .with_summary(false);
+    for (kind, code) in [
+        (ReportKind::Warning, "style::needless_return"),
+        (ReportKind::Warning, "style::snake_case"),
+        (ReportKind::Error, "E0412"),
+        (ReportKind::Help, "E0412"),
+    ] {
+        emitter
+            .emit(Report::new(kind).with_code(code).with_message("message"))
+            .unwrap();
+    }
+    let counts = emitter.finish().unwrap();
+
+    assert_eq!((counts.errors(), counts.warnings()), (1, 1));
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "\
+[style::snake_case] Error: message
+Note: `style::*` is set to `deny`
+[E0412] Warning: message
+Note: `E0412` is set to `warn`
+[E0412] Help: message
+"
+    );
+}
+
+#[test]
+fn test_baseline() {
+    use crate::{Label, PlainText, SourceView};
+
+    let report = |span: std::ops::Range<usize>| {
+        Report::new(ReportKind::Warning)
+            .with_code("W01")
+            .with_message("unused variable")
+            .with_view(SourceView::new("main.rs", span.start).with_label(Label::new(span)))
+    };
+    let cache = || vec![("main.rs", "let x = 1;\nlet y = 2;\n")];
+
+    // Fingerprints are only collected when requested
+    let mut emitter = Emitter::new(PlainText(Vec::new()), cache());
+    emitter.emit(report(4..5)).unwrap();
+    assert!(emitter.fingerprints().is_empty());
+
+    // The first run creates the baseline
+    let mut emitter = Emitter::new(PlainText(Vec::new()), cache()).with_fingerprints(true);
+    emitter.emit(report(4..5)).unwrap();
+    let baseline = emitter.fingerprints().clone();
+
+    let mut output = Vec::new();
+    let mut emitter = Emitter::new(PlainText(&mut output), cache())
+        .with_baseline(baseline)
+        .with_summary(false);
+    emitter.emit(report(4..5)).unwrap();
+    emitter.emit(report(15..16)).unwrap();
+    assert_eq!(emitter.fingerprints().len(), 2);
+    assert_eq!(emitter.finish().unwrap().warnings(), 1);
+
+    let output = String::from_utf8(output).unwrap();
+    assert_eq!(output.matches("Warning: unused variable").count(), 1);
+    assert!(output.contains("let y = 2;"));
+}
+
+#[test]
+fn test_baseline_duplicates() {
+    use crate::{Label, PlainText, SourceView};
+
+    let report = |span: std::ops::Range<usize>| {
+        Report::new(ReportKind::Warning)
+            .with_code("W01")
+            .with_message("unused variable")
+            .with_view(SourceView::new("main.rs", span.start).with_label(Label::new(span)))
+    };
+    let function = "fn a() {\n    let x = 1;\n}\n";
+
+    let mut emitter =
+        Emitter::new(PlainText(Vec::new()), vec![("main.rs", function)]).with_fingerprints(true);
+    emitter.emit(report(17..18)).unwrap();
+    let baseline = emitter.fingerprints().clone();
+
+    // The copied function has the same finding in the same context, which is still new
+    let source = format!("{function}{function}");
+    let mut output = Vec::new();
+    let mut emitter = Emitter::new(PlainText(&mut output), vec![("main.rs", source.as_str())])
+        .with_baseline(baseline)
+        .with_summary(false);
+    emitter.emit(report(17..18)).unwrap();
+    emitter.emit(report(43..44)).unwrap();
+    // Both findings have the same fingerprint
+    let fingerprint = report(17..18).fingerprint(&mut vec![("main.rs", source.as_str())]);
+    assert_eq!(emitter.fingerprints().count(fingerprint), 2);
+    assert_eq!(emitter.finish().unwrap().warnings(), 1);
+    assert!(String::from_utf8(output).unwrap().contains("main.rs:5:9"));
+}
@@ -1,52 +1,183 @@
+use std::hash::{Hash, Hasher};
 use std::{collections::HashMap, fmt::Debug, path::PathBuf};
 use std::{fmt, fs, io};
 
 pub mod tree;
 
 mod style;
-pub use style::{Style, StyleExt};
-use style::{Styled, StyledStr, StyledText};
+use style::StyledText;
+pub use style::{Style, StyleExt, Styled, StyledStr, Underline};
 
 mod backends;
-pub use backends::{Ansi, PlainText};
+pub use backends::{Ansi, Json, Linear, PlainText, Rendered, Sarif, Stream};
+
+mod color;
+pub use color::{Color, ColorChoice, ColorDepth, ColorGenerator, ParseColorError};
+
+mod config;
+pub use config::{Config, LabelHighlight};
+
+mod theme;
+pub use theme::{Theme, ThemeError};
+
+mod locale;
+pub use locale::{English, Locale};
+
+mod catalog;
+pub use catalog::{Catalog, DiagnosticDef, Explanation, TemplateError};
+
+mod lint;
+pub use lint::{LintLevel, LintLevels, ParseLintLevelError};
+
+mod baseline;
+pub use baseline::{Baseline, Fingerprint, ParseBaselineError};
+
+mod emitter;
+pub use emitter::{Counts, Emitter, ReportBuffer};
 
-pub type Color = yansi::Color;
 pub type Span = std::ops::Range<usize>;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "snake_case")
+)]
 // TODO Allow user to define their own ReportKind?
 pub enum ReportKind {
     Error,
     Warning,
     Help,
     Note,
+    /// Deserializing leaks the name, as it has to live for `'static`
     Custom(&'static str, Style),
 }
 
-// TODO Setting the styling should not be hard coded (and maybe happen later on)
+/// Deserialized form of [`ReportKind`] that owns the name of custom kinds
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ReportKindData {
+    Error,
+    Warning,
+    Help,
+    Note,
+    Custom(String, Style),
+}
+
+/// Derived implementations would only deserialize custom kinds from `'static` data,
+/// so the names of custom kinds are interned
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ReportKind {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match ReportKindData::deserialize(deserializer)? {
+            ReportKindData::Error => ReportKind::Error,
+            ReportKindData::Warning => ReportKind::Warning,
+            ReportKindData::Help => ReportKind::Help,
+            ReportKindData::Note => ReportKind::Note,
+            ReportKindData::Custom(name, style) => ReportKind::Custom(intern(name), style),
+        })
+    }
+}
+
+/// Every distinct name is only leaked once
+#[cfg(feature = "serde")]
+fn intern(name: String) -> &'static str {
+    use std::collections::BTreeSet;
+    use std::sync::{Mutex, PoisonError};
+
+    static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+    let mut names = NAMES.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(name) = names.get(name.as_str()) {
+        return name;
+    }
+    let name = Box::leak(name.into_boxed_str());
+    names.insert(name);
+    name
+}
+
+#[cfg(feature = "serde")]
+fn default_locale<'a>() -> &'a dyn Locale {
+    &English
+}
+
 impl ReportKind {
-    fn styled(&self) -> StyledStr<'static> {
-        let base_style = Style::new().bold();
-
-        match self {
-            ReportKind::Error => Styled::new("Error".into(), base_style.fg(Color::Red)),
-            ReportKind::Warning => Styled::new("Warning".into(), base_style.fg(Color::Yellow)),
-            ReportKind::Help => Styled::new("Help".into(), base_style.fg(Color::Blue)),
-            ReportKind::Note => Styled::new("Note".into(), base_style.fg(Color::Green)),
-            ReportKind::Custom(name, style) => Styled::new((*name).into(), *style),
-        }
+    fn styled(&self, theme: &Theme, locale: &dyn Locale) -> StyledStr<'static> {
+        Styled::new(locale.kind_name(*self), theme.style(*self))
     }
 }
 
 #[must_use]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "SourceId: serde::Serialize",
+        deserialize = "SourceId: serde::Deserialize<'de>"
+    ))
+)]
+/// A diagnostic with a message, annotated views of source code and comments
+///
+/// # Serialization
+///
+/// With the `serde` feature, reports can be serialized and deserialized, for example to cache them
+/// or to send them to another process that renders them. The schema is stable within a major version:
+///
+/// ```json
+/// {
+///   "kind": "error",
+///   "code": "E0412",
+///   "url": null,
+///   "message": [{
+///     "text": "cannot find type",
+///     "style": {
+///       "foreground": "red",
+///       "background": "unset",
+///       "attributes": ["bold"],
+///       "underline": null,
+///       "underline_color": "unset"
+///     }
+///   }],
+///   "views": [{
+///     "source_id": "src/lib.rs",
+///     "location": 44,
+///     "labels": [{
+///       "span": { "start": 44, "end": 47 },
+///       "message": null,
+///       "color": "unset",
+///       "visible_whitespace": null
+///     }],
+///     "color_generator": { "seed": 0 }
+///   }],
+///   "comments": []
+/// }
+/// ```
+///
+/// - Kinds are `"error"`, `"warning"`, `"help"`, `"note"` or `{ "custom": [name, style] }`.
+/// - Colors use the format of [`Color`]'s [`Display`](fmt::Display) and [`FromStr`](std::str::FromStr)
+///   implementations, like `"bright-red"`, `"208"` or `"#ff8800"`.
+/// - Styles have the fields `foreground`, `background`, `attributes` (a list of
+///   `"bold"`, `"dim"`, `"italic"`, `"blink"`, `"reverse"`, `"hidden"`, `"strikethrough"` and `"overline"`),
+///   `underline` (`"single"`, `"double"`, `"curly"`, `"dotted"` or `"dashed"`) and `underline_color`.
+///   All of them are serialized, but any of them can be left out when deserializing.
+/// - The [`Config`], highlighter and [`Locale`] are not serialized,
+///   deserialized reports use the defaults of [`Report::new`].
 pub struct Report<'a, SourceId> {
     kind: ReportKind,
     code: Option<String>,
+    /// Documentation of the code
+    url: Option<String>,
     message: Vec<StyledStr<'a>>,
     /// Annotated section of source code
     views: Vec<SourceView<'a, SourceId>>,
     /// Help or note messages
     comments: Vec<(ReportKind, Vec<StyledStr<'a>>)>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    config: Config,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    highlighter: Option<&'a dyn Highlighter<SourceId>>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "default_locale"))]
+    locale: &'a dyn Locale,
 }
 
 impl<'a, SourceId> Report<'a, SourceId> {
@@ -56,9 +187,13 @@ impl<'a, SourceId> Report<'a, SourceId> {
         Self {
             kind,
             code: None,
+            url: None,
             message: vec![],
             views: vec![],
             comments: vec![],
+            config: Config::default(),
+            highlighter: None,
+            locale: &English,
         }
     }
 
@@ -80,6 +215,16 @@ impl<'a, SourceId> Report<'a, SourceId> {
         self.code = Some(format!("{code:02}"));
     }
 
+    /// Link to the documentation of the code, backends that support hyperlinks link the code to it
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn set_url(&mut self, url: impl Into<String>) {
+        self.url = Some(url.into());
+    }
+
     pub fn with_view(mut self, view: SourceView<'a, SourceId>) -> Self {
         self.views.push(view);
         self
@@ -98,6 +243,39 @@ impl<'a, SourceId> Report<'a, SourceId> {
         self.comments.push((kind, comment.parts_vec()));
     }
 
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn set_config(&mut self, config: Config) {
+        self.config = config;
+    }
+
+    pub fn with_highlighter(mut self, highlighter: &'a dyn Highlighter<SourceId>) -> Self {
+        self.highlighter = Some(highlighter);
+        self
+    }
+
+    pub fn set_highlighter(&mut self, highlighter: &'a dyn Highlighter<SourceId>) {
+        self.highlighter = Some(highlighter);
+    }
+
+    /// Translates the text that is added by this crate, like the names of the report kinds
+    pub fn with_locale(mut self, locale: &'a dyn Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn set_locale(&mut self, locale: &'a dyn Locale) {
+        self.locale = locale;
+    }
+
+    /// Stable identifier of this report, see [`Fingerprint`]
+    pub fn fingerprint(&self, cache: &mut impl Cache<SourceId>) -> Fingerprint {
+        Fingerprint::new(self, cache)
+    }
+
     pub fn write<B: Backend>(
         &self,
         backend: &mut B,
@@ -107,12 +285,41 @@ impl<'a, SourceId> Report<'a, SourceId> {
     }
 }
 
-#[derive(Debug)]
+/// Reports are equal if they have the same content,
+/// the config, highlighter and locale only affect how they are displayed and are ignored.
+impl<SourceId: PartialEq> PartialEq for Report<'_, SourceId> {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+            && self.code == other.code
+            && self.url == other.url
+            && self.message == other.message
+            && self.views == other.views
+            && self.comments == other.comments
+    }
+}
+
+impl<SourceId: Eq> Eq for Report<'_, SourceId> {}
+
+impl<SourceId: Hash> Hash for Report<'_, SourceId> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind.hash(state);
+        self.code.hash(state);
+        self.url.hash(state);
+        self.message.hash(state);
+        self.views.hash(state);
+        self.comments.hash(state);
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// Annotated section of source code
 pub struct SourceView<'a, Id> {
     source_id: Id,
     location: usize,
     labels: Vec<Label<'a>>,
+    /// Used to assign colors to labels without a color
+    color_generator: Option<ColorGenerator>,
 }
 
 impl<'a, Id> SourceView<'a, Id> {
@@ -121,6 +328,7 @@ impl<'a, Id> SourceView<'a, Id> {
             source_id,
             location,
             labels: vec![],
+            color_generator: None,
         }
     }
 
@@ -141,13 +349,61 @@ impl<'a, Id> SourceView<'a, Id> {
     pub fn add_labels(&mut self, labels: impl IntoIterator<Item = Label<'a>>) {
         self.labels.extend(labels);
     }
+
+    /// Labels left at [`Color::Unset`] get colors from the generator,
+    /// skipping the colors that are explicitly used by other labels of this view.
+    pub fn with_color_generator(mut self, generator: ColorGenerator) -> Self {
+        self.color_generator = Some(generator);
+        self
+    }
+
+    pub fn set_color_generator(&mut self, generator: ColorGenerator) {
+        self.color_generator = Some(generator);
+    }
+
+    /// Colors of the labels in their order, including the colors assigned by the color generator
+    pub fn label_colors(&self) -> Vec<Color> {
+        let mut generated = self.generated_colors();
+        self.labels
+            .iter()
+            .map(|label| match label.color {
+                Color::Unset => generated.next().unwrap_or(Color::Unset),
+                color => color,
+            })
+            .collect()
+    }
+
+    /// Color that the next added label left at [`Color::Unset`] gets from the color generator,
+    /// so messages can refer to it. `None` if there is no color generator.
+    ///
+    /// Adding labels with an explicit color that was already generated changes the assigned colors.
+    pub fn next_label_color(&self) -> Option<Color> {
+        let unset = (self.labels.iter())
+            .filter(|label| label.color == Color::Unset)
+            .count();
+        self.generated_colors().nth(unset)
+    }
+
+    /// Colors of the generator without the colors that are explicitly used by labels
+    fn generated_colors(&self) -> impl Iterator<Item = Color> + '_ {
+        let used = (self.labels.iter())
+            .map(|label| label.color)
+            .filter(|color| *color != Color::Unset)
+            .collect::<Vec<_>>();
+        (self.color_generator.clone().into_iter())
+            .flatten()
+            .filter(move |color| !used.contains(color))
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Label<'a> {
     span: Span,
     message: Option<Vec<StyledStr<'a>>>,
     color: Color,
+    /// Overwrites [`Config::with_visible_whitespace`] for this label
+    visible_whitespace: Option<bool>,
 }
 
 impl<'a> Label<'a> {
@@ -156,6 +412,7 @@ impl<'a> Label<'a> {
             span,
             message: None,
             color: Color::Unset,
+            visible_whitespace: None,
         }
     }
 
@@ -176,6 +433,15 @@ impl<'a> Label<'a> {
     pub fn set_color(&mut self, color: Color) {
         self.color = color;
     }
+
+    pub fn with_visible_whitespace(mut self, enabled: bool) -> Self {
+        self.visible_whitespace = Some(enabled);
+        self
+    }
+
+    pub fn set_visible_whitespace(&mut self, enabled: bool) {
+        self.visible_whitespace = Some(enabled);
+    }
 }
 
 pub trait Cache<Id: ?Sized> {
@@ -189,6 +455,13 @@ pub trait Cache<Id: ?Sized> {
 
     /// Display the given Id. as a single inline value.
     fn display_id<'a>(&self, id: &'a Id) -> Option<Self::DisplayedId<'a>>;
+
+    /// Target of a hyperlink to a location in the given source, like `file:///src/main.rs`.
+    ///
+    /// Only used by backends that can display hyperlinks, `line` and `col` start at 1.
+    fn link(&self, _id: &Id, _line: usize, _col: usize) -> Option<String> {
+        None
+    }
 }
 
 impl<Id, C: Cache<Id>> Cache<Id> for &mut C {
@@ -202,6 +475,10 @@ impl<Id, C: Cache<Id>> Cache<Id> for &mut C {
     fn display_id<'b>(&self, id: &'b Id) -> Option<Self::DisplayedId<'b>> {
         C::display_id(self, id)
     }
+
+    fn link(&self, id: &Id, line: usize, col: usize) -> Option<String> {
+        C::link(self, id, line, col)
+    }
 }
 
 impl<'a> Cache<&'a str> for Vec<(&'a str, &'a str)> {
@@ -237,9 +514,44 @@ impl Cache<()> for () {
     }
 }
 
-#[derive(Debug, Default)]
+/// Syntax highlighting for the source code displayed in a [`SourceView`]
+pub trait Highlighter<Id: ?Sized> {
+    /// Splits one line of source code into styled parts.
+    ///
+    /// The text of all parts joined together has to be equal to `line`,
+    /// parts with the default style are left unstyled.
+    /// Lines are highlighted one by one, so constructs spanning multiple lines
+    /// (like block comments) have to be tracked by the implementation if needed.
+    fn highlight<'a>(&self, id: &Id, line: &'a str) -> Vec<StyledStr<'a>>;
+}
+
+#[derive(Debug)]
 pub struct FileCache {
     files: HashMap<PathBuf, String>,
+    link_format: String,
+}
+
+impl Default for FileCache {
+    fn default() -> Self {
+        Self {
+            files: HashMap::new(),
+            link_format: "file://{path}".into(),
+        }
+    }
+}
+
+impl FileCache {
+    /// Format of hyperlinks to files, `{path}` is replaced with the absolute path of the file,
+    /// `{line}` and `{col}` with the location. Defaults to `file://{path}`,
+    /// editors often have their own scheme like `vscode://file{path}:{line}:{col}`.
+    pub fn with_link_format(mut self, format: impl Into<String>) -> Self {
+        self.link_format = format.into();
+        self
+    }
+
+    pub fn set_link_format(&mut self, format: impl Into<String>) {
+        self.link_format = format.into();
+    }
 }
 
 impl Cache<PathBuf> for FileCache {
@@ -256,6 +568,43 @@ impl Cache<PathBuf> for FileCache {
     fn display_id<'a>(&self, id: &'a PathBuf) -> Option<std::path::Display<'a>> {
         Some(id.display())
     }
+
+    fn link(&self, id: &PathBuf, line: usize, col: usize) -> Option<String> {
+        let path = std::path::absolute(id).ok()?;
+        let path = percent_encode_path(&path.to_string_lossy());
+        Some(
+            self.link_format
+                .replace("{path}", &path)
+                .replace("{line}", &line.to_string())
+                .replace("{col}", &col.to_string()),
+        )
+    }
+}
+
+/// Encodes all characters of a path that are not allowed in URLs, separators are kept
+fn percent_encode_path(path: &str) -> String {
+    let mut encoded = percent_encode(path);
+    if !encoded.starts_with('/') {
+        encoded.insert(0, '/');
+    }
+    encoded
+}
+
+/// Like [`percent_encode_path`], but relative paths stay relative URL references
+pub(crate) fn percent_encode(path: &str) -> String {
+    let mut encoded = String::new();
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            // Windows paths like `C:\src` become `/C:/src`
+            b'\\' => encoded.push('/'),
+            b':' if encoded.len() == 1 => encoded.push(':'),
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
 }
 
 pub trait Backend {
@@ -266,4 +615,16 @@ pub trait Backend {
         report: &Report<SourceId>,
         cache: &mut impl Cache<SourceId>,
     ) -> Result<(), Self::Error>;
+
+    /// Backends that do not display explanations can keep the default, which writes nothing
+    fn write_explanation(&mut self, _explanation: &Explanation) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Writes what the backend has collected, backends that write every report
+    /// right away can keep the default, which does nothing.
+    /// [`Emitter::finish`] calls it, other users have to call it after the last report.
+    fn finish(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
 }
@@ -0,0 +1,168 @@
+use crate::ReportKind;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// How reports with a code are emitted, see [`LintLevels`]
+pub enum LintLevel {
+    /// Reports are not emitted
+    Allow,
+    /// Reports are emitted as warnings
+    Warn,
+    /// Reports are emitted as errors
+    Deny,
+    /// Like [`LintLevel::Deny`], but can not be changed by more specific settings
+    Forbid,
+}
+
+impl LintLevel {
+    /// Kind of the reports, `None` if they are not emitted
+    pub fn kind(self) -> Option<ReportKind> {
+        match self {
+            LintLevel::Allow => None,
+            LintLevel::Warn => Some(ReportKind::Warning),
+            LintLevel::Deny | LintLevel::Forbid => Some(ReportKind::Error),
+        }
+    }
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+            LintLevel::Forbid => "forbid",
+        })
+    }
+}
+
+impl FromStr for LintLevel {
+    type Err = ParseLintLevelError;
+
+    /// Parses the format written by [`Display`](fmt::Display), case insensitive
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_ascii_lowercase().as_str() {
+            "allow" => Ok(LintLevel::Allow),
+            "warn" => Ok(LintLevel::Warn),
+            "deny" => Ok(LintLevel::Deny),
+            "forbid" => Ok(LintLevel::Forbid),
+            _ => Err(ParseLintLevelError {
+                input: input.into(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLintLevelError {
+    input: String,
+}
+
+impl fmt::Display for ParseLintLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid lint level `{}`, expected `allow`, `warn`, `deny` or `forbid`",
+            self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseLintLevelError {}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Levels of reports by their code, consulted by the [`Emitter`](crate::Emitter)
+///
+/// A setting applies to a single code like `style::needless_return`
+/// or to a group of codes like `style::*`, which also contains nested groups like `style::naming::*`.
+/// The most specific setting for a code wins, settings for the same code or group replace each other.
+/// A [`LintLevel::Forbid`] setting always wins over the settings of the codes it contains.
+pub struct LintLevels {
+    levels: Vec<(String, LintLevel)>,
+}
+
+impl LintLevels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_level(mut self, pattern: impl Into<String>, level: LintLevel) -> Self {
+        self.set_level(pattern, level);
+        self
+    }
+
+    pub fn set_level(&mut self, pattern: impl Into<String>, level: LintLevel) {
+        let pattern = pattern.into();
+        self.levels.retain(|(other, _)| *other != pattern);
+        self.levels.push((pattern, level));
+    }
+
+    /// The level of a code and the pattern of the setting it comes from,
+    /// `None` if there is no setting for the code
+    pub fn level(&self, code: &str) -> Option<(&str, LintLevel)> {
+        let mut result: Option<(usize, &str, LintLevel)> = None;
+        for (pattern, level) in &self.levels {
+            let Some(specificity) = specificity(pattern, code) else {
+                continue;
+            };
+            let wins = match result {
+                None => true,
+                // Forbid can only be replaced by a less specific forbid
+                Some((current, _, LintLevel::Forbid)) => {
+                    *level == LintLevel::Forbid && specificity < current
+                }
+                Some(_) if *level == LintLevel::Forbid => true,
+                Some((current, ..)) => specificity > current,
+            };
+            if wins {
+                result = Some((specificity, pattern, *level));
+            }
+        }
+        result.map(|(_, pattern, level)| (pattern, level))
+    }
+}
+
+/// Length of the part of the code that is matched by the pattern,
+/// exact matches are one longer than the code so they are more specific than any group.
+fn specificity(pattern: &str, code: &str) -> Option<usize> {
+    if pattern == code {
+        return Some(code.len() + 1);
+    }
+    let group = pattern.strip_suffix('*')?;
+    // Groups are `*` or end with `::`
+    if !(group.is_empty() || group.ends_with("::")) {
+        return None;
+    }
+    code.starts_with(group).then_some(group.len())
+}
+
+#[test]
+fn test_lint_levels() {
+    let levels = LintLevels::new()
+        .with_level("*", LintLevel::Warn)
+        .with_level("style::*", LintLevel::Deny)
+        .with_level("style::needless_return", LintLevel::Allow)
+        .with_level("correctness::*", LintLevel::Forbid)
+        .with_level("correctness::unused", LintLevel::Allow);
+
+    assert_eq!(levels.level("E0412"), Some(("*", LintLevel::Warn)));
+    assert_eq!(
+        levels.level("style::naming::snake_case"),
+        Some(("style::*", LintLevel::Deny))
+    );
+    assert_eq!(
+        levels.level("style::needless_return"),
+        Some(("style::needless_return", LintLevel::Allow))
+    );
+    assert_eq!(
+        levels.level("correctness::unused"),
+        Some(("correctness::*", LintLevel::Forbid))
+    );
+    // `style::*` only matches the group
+    assert_eq!(levels.level("stylex"), Some(("*", LintLevel::Warn)));
+    assert_eq!(LintLevels::new().level("E0412"), None);
+
+    assert_eq!("Deny".parse(), Ok(LintLevel::Deny));
+    assert!("denied".parse::<LintLevel>().is_err());
+}
@@ -0,0 +1,214 @@
+use crate::{LintLevel, ReportKind};
+use std::borrow::Cow;
+
+/// Translations of all text that is added to reports by this crate
+///
+/// All methods default to English, so a translation can be incomplete.
+/// Translated text may use any script, the layout is based on the unicode width of the text.
+pub trait Locale {
+    /// Name of the report kind, [`ReportKind::Custom`] names should usually be returned as they are
+    fn kind_name(&self, kind: ReportKind) -> Cow<'static, str> {
+        match kind {
+            ReportKind::Error => "Error".into(),
+            ReportKind::Warning => "Warning".into(),
+            ReportKind::Help => "Help".into(),
+            ReportKind::Note => "Note".into(),
+            ReportKind::Custom(name, _) => name.into(),
+        }
+    }
+
+    /// Number of reports of one kind, like "1 warning" or "3 errors"
+    fn kind_count(&self, kind: ReportKind, count: usize) -> String {
+        let name = self.kind_name(kind).to_lowercase();
+        match count {
+            1 => format!("{count} {name}"),
+            _ => format!("{count} {name}s"),
+        }
+    }
+
+    /// Displayed instead of the name of a source that the cache can not display
+    fn unknown_source(&self) -> Cow<'static, str> {
+        "<unknown>".into()
+    }
+
+    /// Displayed instead of the message of a label without one
+    fn empty_label(&self) -> Cow<'static, str> {
+        "<empty label>".into()
+    }
+
+    /// Note explaining the escapes of invisible characters, `chars` is a list like "U+202E, U+2066"
+    fn invisible_chars(&self, chars: &str) -> String {
+        format!(
+            "labeled source code contains invisible characters ({chars}), \
+            they are displayed as visible escapes"
+        )
+    }
+
+    /// Note added to reports whose kind was changed by a [`LintLevels`](crate::LintLevels) setting
+    fn lint_level(&self, pattern: &str, level: LintLevel) -> String {
+        format!("`{pattern}` is set to `{level}`")
+    }
+
+    /// Note added to warnings that are reported as errors
+    fn denied_warning(&self) -> String {
+        "warnings are denied and reported as errors".into()
+    }
+
+    /// Written once the maximum number of errors is reached
+    fn aborting(&self, errors: usize) -> String {
+        format!(
+            "aborting after {}",
+            self.kind_count(ReportKind::Error, errors)
+        )
+    }
+
+    /// Note about reports that were not written because there are many similar reports
+    fn more_similar(&self, kind: ReportKind, count: usize) -> String {
+        let name = self.kind_name(kind).to_lowercase();
+        match count {
+            1 => format!("and 1 more similar {name}"),
+            _ => format!("and {count} more similar {name}s"),
+        }
+    }
+
+    /// Final summary after all reports were written, `None` if nothing should be written.
+    ///
+    /// `subject` describes what failed, like "could not compile `crate` (lib)".
+    fn summary(&self, subject: Option<&str>, errors: usize, warnings: usize) -> Option<String> {
+        let warnings = (warnings > 0)
+            .then(|| format!("{} emitted", self.kind_count(ReportKind::Warning, warnings)));
+        if errors == 0 {
+            return match subject {
+                Some(subject) => warnings.map(|warnings| format!("{subject}: {warnings}")),
+                None => warnings,
+            };
+        }
+
+        let previous = match errors {
+            1 => "previous error".into(),
+            _ => format!("{errors} previous errors"),
+        };
+        let subject = subject.unwrap_or("aborting");
+        Some(match warnings {
+            Some(warnings) => format!("{subject} due to {previous}; {warnings}"),
+            None => format!("{subject} due to {previous}"),
+        })
+    }
+
+    /// Lists the codes of reports that have a long explanation, `codes` contains at least two codes.
+    /// `kind` is the kind of all of them, `None` if their kinds differ.
+    fn explained_codes(&self, kind: Option<ReportKind>, codes: &[&str]) -> String {
+        let name = match kind {
+            Some(kind) => format!("{}s", self.kind_name(kind).to_lowercase()),
+            None => "diagnostics".into(),
+        };
+        format!(
+            "Some {name} have detailed explanations: {}.",
+            codes.join(", ")
+        )
+    }
+
+    /// Hint on how to show the explanation of `code`,
+    /// `count` is the number of codes that have an explanation and `kind` is like in [`Locale::explained_codes`].
+    fn explain_hint(
+        &self,
+        command: &str,
+        code: &str,
+        count: usize,
+        kind: Option<ReportKind>,
+    ) -> String {
+        let name = kind.map_or("diagnostic".into(), |kind| {
+            self.kind_name(kind).to_lowercase()
+        });
+        let article = match name.starts_with(['a', 'e', 'i', 'o', 'u']) {
+            true => "an",
+            false => "a",
+        };
+        match count {
+            1 => format!("For more information about this {name}, try `{command} {code}`."),
+            _ => format!("For more information about {article} {name}, try `{command} {code}`."),
+        }
+    }
+
+    /// Introduces a code example of an explanation in the [`Linear`](crate::Linear) backend
+    fn linear_code_example(&self, lines: usize) -> String {
+        match lines {
+            1 => "Code example with 1 line:".into(),
+            _ => format!("Code example with {lines} lines:"),
+        }
+    }
+
+    /// Location of a source view in the [`Linear`](crate::Linear) backend,
+    /// `name` is `None` if the cache can not display the source name
+    /// and `position` is `None` if the source could not be fetched.
+    fn linear_location(&self, name: Option<&str>, position: Option<(usize, usize)>) -> String {
+        let name = match name {
+            Some(name) => format!("file {name}"),
+            None => "an unknown file".into(),
+        };
+        match position {
+            Some((line, col)) => format!("In {name} at line {line}, column {col}:"),
+            None => format!("In {name}."),
+        }
+    }
+
+    /// One line of source code in the [`Linear`](crate::Linear) backend, `text` is empty for empty lines
+    fn linear_source_line(&self, line: usize, text: &str) -> String {
+        match text.is_empty() {
+            true => format!("Line {line} is empty."),
+            false => format!("Line {line}: {text}"),
+        }
+    }
+
+    /// Position and message of a label in the [`Linear`](crate::Linear) backend,
+    /// positions are `(line, column)` of the first and last labeled character.
+    fn linear_label(
+        &self,
+        (start_line, start_col): (usize, usize),
+        (end_line, end_col): (usize, usize),
+        message: Option<&str>,
+    ) -> String {
+        let position = match start_line == end_line {
+            true => format!("Label on line {start_line} from column {start_col} to {end_col}"),
+            false => format!(
+                "Label from line {start_line}, column {start_col} to line {end_line}, column {end_col}"
+            ),
+        };
+        match message {
+            Some(message) => format!("{position}: {message}"),
+            None => format!("{position}."),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+/// The default locale
+pub struct English;
+
+impl Locale for English {}
+
+#[test]
+fn test_kind_count() {
+    assert_eq!(English.kind_count(ReportKind::Error, 0), "0 errors");
+    assert_eq!(English.kind_count(ReportKind::Error, 1), "1 error");
+    assert_eq!(English.kind_count(ReportKind::Warning, 15), "15 warnings");
+}
+
+#[test]
+fn test_summary() {
+    assert_eq!(English.summary(None, 0, 0), None);
+    assert_eq!(
+        English.summary(Some("`x`"), 0, 1).unwrap(),
+        "`x`: 1 warning emitted"
+    );
+    assert_eq!(
+        English.summary(None, 1, 0).unwrap(),
+        "aborting due to previous error"
+    );
+    assert_eq!(
+        English
+            .summary(Some("could not compile `x`"), 2, 3)
+            .unwrap(),
+        "could not compile `x` due to 2 previous errors; 3 warnings emitted"
+    );
+}
@@ -1,14 +1,24 @@
 use crate::Color;
 use std::borrow::Cow;
 
-#[derive(Debug, Default, Clone, Copy)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct Style {
     /// Color of the text
     pub(crate) foreground: Color,
     /// Color of the background
     pub(crate) background: Color,
     /// Additional formatting data
+    #[cfg_attr(feature = "serde", serde(rename = "attributes"))]
     flags: StyleFlags,
+    /// Kind of underline, `None` if the text is not underlined
+    pub(crate) underline: Option<Underline>,
+    /// Color of the underline, the foreground color is used if this is unset
+    pub(crate) underline_color: Color,
 }
 
 impl Style {
@@ -26,45 +36,162 @@ impl Style {
         self
     }
 
-    pub fn bold(mut self) -> Self {
-        self.flags = match self.flags {
-            StyleFlags::None | StyleFlags::Bold => StyleFlags::Bold,
-            StyleFlags::Italic | StyleFlags::BoldItalic => StyleFlags::BoldItalic,
-        };
+    pub fn underline(self) -> Self {
+        self.underline_kind(Underline::Single)
+    }
+
+    pub fn underline_kind(mut self, underline: Underline) -> Self {
+        self.underline = Some(underline);
         self
     }
 
-    pub fn italic(mut self) -> Self {
-        self.flags = match self.flags {
-            StyleFlags::None | StyleFlags::Italic => StyleFlags::Italic,
-            StyleFlags::Bold | StyleFlags::BoldItalic => StyleFlags::BoldItalic,
-        };
+    pub fn underline_color(mut self, color: Color) -> Self {
+        self.underline_color = color;
         self
     }
 
-    pub fn is_bold(&self) -> bool {
-        matches!(self.flags, StyleFlags::Bold | StyleFlags::BoldItalic)
+    /// Fills in all attributes that are not set on `self` from `parent`
+    pub(crate) fn inherit(self, parent: &Style) -> Self {
+        let unset_or = |color, parent| match color {
+            Color::Unset => parent,
+            color => color,
+        };
+        Self {
+            foreground: unset_or(self.foreground, parent.foreground),
+            background: unset_or(self.background, parent.background),
+            flags: StyleFlags(self.flags.0 | parent.flags.0),
+            underline: self.underline.or(parent.underline),
+            underline_color: unset_or(self.underline_color, parent.underline_color),
+        }
+    }
+
+    pub fn set_underline(&mut self, enabled: bool) {
+        self.underline = enabled.then_some(Underline::Single);
+    }
+
+    pub fn is_underline(&self) -> bool {
+        self.underline.is_some()
+    }
+}
+
+/// Generates a builder, setter and query method for each flag
+macro_rules! flag_methods {
+    ($($flag:ident: $builder:ident, $setter:ident, $query:ident;)*) => {
+        impl Style {
+            $(
+                pub fn $builder(mut self) -> Self {
+                    self.flags.set(StyleFlags::$flag, true);
+                    self
+                }
+
+                pub fn $setter(&mut self, enabled: bool) {
+                    self.flags.set(StyleFlags::$flag, enabled);
+                }
+
+                pub fn $query(&self) -> bool {
+                    self.flags.contains(StyleFlags::$flag)
+                }
+            )*
+        }
+    };
+}
+
+flag_methods! {
+    BOLD: bold, set_bold, is_bold;
+    DIM: dim, set_dim, is_dim;
+    ITALIC: italic, set_italic, is_italic;
+    BLINK: blink, set_blink, is_blink;
+    REVERSE: reverse, set_reverse, is_reverse;
+    HIDDEN: hidden, set_hidden, is_hidden;
+    STRIKETHROUGH: strikethrough, set_strikethrough, is_strikethrough;
+    OVERLINE: overline, set_overline, is_overline;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+/// Support for anything other than [`Underline::Single`] depends on the terminal,
+/// unsupported kinds are usually displayed as a single underline.
+pub enum Underline {
+    Single,
+    Double,
+    Curly,
+    Dotted,
+    Dashed,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+/// Set of text attributes, one bit per attribute
+struct StyleFlags(u8);
+
+impl StyleFlags {
+    const BOLD: u8 = 1 << 0;
+    const DIM: u8 = 1 << 1;
+    const ITALIC: u8 = 1 << 2;
+    const BLINK: u8 = 1 << 3;
+    const REVERSE: u8 = 1 << 4;
+    const HIDDEN: u8 = 1 << 5;
+    const STRIKETHROUGH: u8 = 1 << 6;
+    const OVERLINE: u8 = 1 << 7;
+
+    /// Names of the flags, in the order of their bits
+    #[cfg(feature = "serde")]
+    const NAMES: [&'static str; 8] = [
+        "bold",
+        "dim",
+        "italic",
+        "blink",
+        "reverse",
+        "hidden",
+        "strikethrough",
+        "overline",
+    ];
+
+    fn set(&mut self, flag: u8, enabled: bool) {
+        if enabled {
+            self.0 |= flag;
+        } else {
+            self.0 &= !flag;
+        }
     }
 
-    pub fn is_italic(&self) -> bool {
-        matches!(self.flags, StyleFlags::Italic | StyleFlags::BoldItalic)
+    fn contains(&self, flag: u8) -> bool {
+        self.0 & flag != 0
     }
+}
 
-    // TODO add set_bold and set_italic
+/// Serialized as the list of names of the set flags, like `["bold", "italic"]`
+#[cfg(feature = "serde")]
+impl serde::Serialize for StyleFlags {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            (StyleFlags::NAMES.iter().enumerate())
+                .filter(|(bit, _)| self.contains(1 << bit))
+                .map(|(_, name)| name),
+        )
+    }
 }
 
-#[derive(Debug, Default, Clone, Copy)]
-// TODO How and what information should this store? Bitflags?
-enum StyleFlags {
-    #[default]
-    None,
-    Bold,
-    Italic,
-    BoldItalic,
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for StyleFlags {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut flags = StyleFlags::default();
+        for name in Vec::<Cow<str>>::deserialize(deserializer)? {
+            let bit = (StyleFlags::NAMES.iter().position(|flag| *flag == name))
+                .ok_or_else(|| serde::de::Error::unknown_variant(&name, &StyleFlags::NAMES))?;
+            flags.set(1 << bit, true);
+        }
+        Ok(flags)
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Styled<T: std::fmt::Debug> {
+    #[cfg_attr(feature = "serde", serde(rename = "text"))]
     inner: T,
     style: Style,
 }
@@ -98,7 +225,7 @@ impl<T: Clone + std::fmt::Debug> Clone for Styled<T> {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
-            style: self.style.clone(),
+            style: self.style,
         }
     }
 }
@@ -161,7 +288,53 @@ pub trait StyleExt<'a>: Sized + Into<Cow<'a, str>> {
     fn italic(self) -> StyledStr<'a> {
         Styled::new(self.into(), Style::new().italic())
     }
+
+    fn underline(self) -> StyledStr<'a> {
+        Styled::new(self.into(), Style::new().underline())
+    }
+
+    fn dim(self) -> StyledStr<'a> {
+        Styled::new(self.into(), Style::new().dim())
+    }
+
+    fn blink(self) -> StyledStr<'a> {
+        Styled::new(self.into(), Style::new().blink())
+    }
+
+    fn reverse(self) -> StyledStr<'a> {
+        Styled::new(self.into(), Style::new().reverse())
+    }
+
+    fn hidden(self) -> StyledStr<'a> {
+        Styled::new(self.into(), Style::new().hidden())
+    }
+
+    fn strikethrough(self) -> StyledStr<'a> {
+        Styled::new(self.into(), Style::new().strikethrough())
+    }
+
+    fn overline(self) -> StyledStr<'a> {
+        Styled::new(self.into(), Style::new().overline())
+    }
 }
 
 impl<'a> StyleExt<'a> for &'a str {}
 impl StyleExt<'_> for String {}
+
+#[test]
+fn test_style_flags() {
+    let mut style = Style::new().bold().italic().strikethrough();
+    assert!(style.is_bold() && style.is_italic() && style.is_strikethrough());
+    assert!(!style.is_dim() && !style.is_overline());
+
+    style.set_italic(false);
+    style.set_overline(true);
+    assert!(style.is_bold() && !style.is_italic() && style.is_overline());
+
+    style.set_underline(true);
+    assert_eq!(style.underline, Some(Underline::Single));
+    style.set_underline(false);
+    assert!(!style.is_underline());
+
+    assert_eq!(Style::new().bold().bold(), Style::new().bold());
+}
@@ -0,0 +1,428 @@
+use crate::{Color, Label, Report, ReportKind, SourceView, Span, Style, Underline};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Styles and characters used to display a [`Report`]
+///
+/// Themes can be loaded from a simple text format with one `key = value` pair per line,
+/// empty lines and lines starting with `#` are ignored:
+///
+/// ```text
+/// # Styles are a list of attributes and colors, `on <color>` sets the background
+/// style.error = bold bright-red
+/// style.border = dim
+/// # Characters are a single character
+/// char.vbar = |
+/// ```
+///
+/// Valid keys are `style.error`, `style.warning`, `style.help`, `style.note`, `style.border`,
+/// `char.hbar`, `char.vbar`, `char.ltop`, `char.rbot`, `char.space`, `char.tab` and `char.nbsp`.
+pub struct Theme {
+    pub(crate) error: Style,
+    pub(crate) warning: Style,
+    pub(crate) help: Style,
+    pub(crate) note: Style,
+    /// Style of the border around source code
+    pub(crate) border: Style,
+    /// Horizontal bar of the border
+    pub(crate) hbar: char,
+    /// Vertical bar of the border
+    pub(crate) vbar: char,
+    /// Top left corner of the border
+    pub(crate) ltop: char,
+    /// Bottom right corner of the border
+    pub(crate) rbot: char,
+    /// Visible whitespace glyphs
+    pub(crate) space: char,
+    pub(crate) tab: char,
+    pub(crate) nbsp: char,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let base_style = Style::new().bold();
+        Self {
+            error: base_style.fg(Color::Red),
+            warning: base_style.fg(Color::Yellow),
+            help: base_style.fg(Color::Blue),
+            note: base_style.fg(Color::Green),
+            border: Style::default(),
+            hbar: '─',
+            vbar: '│',
+            ltop: '╭',
+            rbot: '╯',
+            space: '·',
+            tab: '→',
+            nbsp: '⍽',
+        }
+    }
+}
+
+const KEYS: [&str; 12] = [
+    "style.error",
+    "style.warning",
+    "style.help",
+    "style.note",
+    "style.border",
+    "char.hbar",
+    "char.vbar",
+    "char.ltop",
+    "char.rbot",
+    "char.space",
+    "char.tab",
+    "char.nbsp",
+];
+
+impl Theme {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a theme, keys that are not set keep their default value
+    pub fn parse(text: &str) -> Result<Self, Vec<ThemeError>> {
+        let mut theme = Self::default();
+        theme.apply_str(text)?;
+        Ok(theme)
+    }
+
+    pub fn style(&self, kind: ReportKind) -> Style {
+        match kind {
+            ReportKind::Error => self.error,
+            ReportKind::Warning => self.warning,
+            ReportKind::Help => self.help,
+            ReportKind::Note => self.note,
+            ReportKind::Custom(_, style) => style,
+        }
+    }
+
+    /// Overwrites all keys that are set in `text`,
+    /// nothing is changed if there are any errors.
+    pub fn apply_str(&mut self, text: &str) -> Result<(), Vec<ThemeError>> {
+        let mut theme = *self;
+        let mut errors = vec![];
+
+        let mut line_start = 0;
+        for line in text.split_inclusive('\n') {
+            let offset = line_start;
+            line_start += line.len();
+
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                errors.push(ThemeError {
+                    span: Some(trimmed_span(line, offset)),
+                    message: "expected `key = value`".into(),
+                    help: None,
+                });
+                continue;
+            };
+
+            let key_span = trimmed_span(key, offset);
+            let value_span = trimmed_span(value, offset + key.len() + 1);
+
+            match theme.set(key.trim(), value.trim()) {
+                Ok(()) => {}
+                Err(SetError::UnknownKey { closest }) => errors.push(ThemeError {
+                    span: Some(key_span),
+                    message: format!("unknown theme key `{}`", key.trim()),
+                    help: Some(key_help(closest.map(str::to_string))),
+                }),
+                Err(SetError::InvalidValue { message, help }) => errors.push(ThemeError {
+                    span: Some(value_span),
+                    message,
+                    help,
+                }),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        *self = theme;
+        Ok(())
+    }
+
+    /// Overwrites the keys set by environment variables, for example
+    /// `style.error` is set by `{prefix}STYLE_ERROR` and `char.vbar` by `{prefix}CHAR_VBAR`.
+    /// Nothing is changed if there are any errors.
+    pub fn apply_env(&mut self, prefix: &str) -> Result<(), Vec<ThemeError>> {
+        self.apply_vars(prefix, std::env::vars())
+    }
+
+    fn apply_vars(
+        &mut self,
+        prefix: &str,
+        vars: impl Iterator<Item = (String, String)>,
+    ) -> Result<(), Vec<ThemeError>> {
+        let mut theme = *self;
+        let mut errors = vec![];
+
+        for (name, value) in vars {
+            let Some(key) = name.strip_prefix(prefix) else {
+                continue;
+            };
+            let key = key.to_lowercase().replacen('_', ".", 1);
+
+            match theme.set(&key, value.trim()) {
+                Ok(()) => {}
+                Err(SetError::UnknownKey { closest }) => errors.push(ThemeError {
+                    span: None,
+                    message: format!("unknown theme variable `{name}`"),
+                    help: Some(key_help(closest.map(|key| {
+                        format!("{prefix}{}", key.replace('.', "_").to_uppercase())
+                    }))),
+                }),
+                Err(SetError::InvalidValue { message, help }) => errors.push(ThemeError {
+                    span: None,
+                    message: format!("invalid value for `{name}`: {message}"),
+                    help,
+                }),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        *self = theme;
+        Ok(())
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), SetError> {
+        let (group, name) = key.split_once('.').unwrap_or(("", key));
+        match group {
+            "style" => {
+                let style = match name {
+                    "error" => &mut self.error,
+                    "warning" => &mut self.warning,
+                    "help" => &mut self.help,
+                    "note" => &mut self.note,
+                    "border" => &mut self.border,
+                    _ => return Err(unknown_key(key)),
+                };
+                *style = parse_style(value)?;
+            }
+            "char" => {
+                let c = match name {
+                    "hbar" => &mut self.hbar,
+                    "vbar" => &mut self.vbar,
+                    "ltop" => &mut self.ltop,
+                    "rbot" => &mut self.rbot,
+                    "space" => &mut self.space,
+                    "tab" => &mut self.tab,
+                    "nbsp" => &mut self.nbsp,
+                    _ => return Err(unknown_key(key)),
+                };
+                let mut chars = value.chars();
+                *c = match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => {
+                        return Err(SetError::InvalidValue {
+                            message: format!("expected a single character, found `{value}`"),
+                            help: None,
+                        })
+                    }
+                };
+            }
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+/// Span of `text` without surrounding whitespace, `offset` is the index of `text`
+fn trimmed_span(text: &str, offset: usize) -> Span {
+    let start = offset + text.len() - text.trim_start().len();
+    start..start + text.trim().len()
+}
+
+enum SetError {
+    UnknownKey {
+        /// Most similar valid key
+        closest: Option<&'static str>,
+    },
+    InvalidValue {
+        message: String,
+        help: Option<String>,
+    },
+}
+
+fn unknown_key(key: &str) -> SetError {
+    let closest = KEYS
+        .iter()
+        .map(|known| (edit_distance(key, known), *known))
+        .min()
+        .filter(|(distance, _)| *distance <= 3)
+        .map(|(_, known)| known);
+    SetError::UnknownKey { closest }
+}
+
+fn key_help(closest: Option<String>) -> String {
+    match closest {
+        Some(closest) => format!("did you mean `{closest}`?"),
+        None => format!(
+            "valid keys are {}",
+            KEYS.map(|key| format!("`{key}`")).join(", ")
+        ),
+    }
+}
+
+/// Parses a list of attributes and colors like `bold red on black`
+fn parse_style(value: &str) -> Result<Style, SetError> {
+    let error = |message: String| SetError::InvalidValue {
+        message,
+        help: Some(
+            "styles are a list of attributes (like `bold`, `italic` or `curly-underline`), \
+            a foreground color and `on` followed by a background color"
+                .into(),
+        ),
+    };
+
+    let mut style = Style::new();
+    let mut words = value.split_whitespace();
+    while let Some(word) = words.next() {
+        style = match word {
+            "bold" => style.bold(),
+            "dim" => style.dim(),
+            "italic" => style.italic(),
+            "underline" => style.underline(),
+            "double-underline" => style.underline_kind(Underline::Double),
+            "curly-underline" => style.underline_kind(Underline::Curly),
+            "dotted-underline" => style.underline_kind(Underline::Dotted),
+            "dashed-underline" => style.underline_kind(Underline::Dashed),
+            "blink" => style.blink(),
+            "reverse" => style.reverse(),
+            "hidden" => style.hidden(),
+            "strikethrough" => style.strikethrough(),
+            "overline" => style.overline(),
+            "on" => {
+                let color = words
+                    .next()
+                    .ok_or_else(|| error("expected a background color after `on`".into()))?;
+                style.bg(color.parse().map_err(|err| error(format!("{err}")))?)
+            }
+            color => style.fg(color
+                .parse()
+                .map_err(|_| error(format!("`{color}` is neither an attribute nor a color")))?),
+        };
+    }
+    Ok(style)
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous + (a != *b) as usize;
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Invalid entry of a theme, use [`ThemeError::report`] to display it
+pub struct ThemeError {
+    /// Location in the parsed text, `None` for environment variables
+    span: Option<Span>,
+    message: String,
+    help: Option<String>,
+}
+
+impl ThemeError {
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Creates a report for this error, `source_id` has to refer to the text the theme was parsed from.
+    pub fn report<Id>(&self, source_id: Id) -> Report<'_, Id> {
+        let mut report = Report::new(ReportKind::Error).with_message(self.message.as_str());
+        if let Some(span) = &self.span {
+            report.add_view(
+                SourceView::new(source_id, span.start)
+                    .with_label(Label::new(span.clone()).with_message(self.message.as_str())),
+            );
+        }
+        if let Some(help) = &self.help {
+            report.set_comment(ReportKind::Help, help.as_str());
+        }
+        report
+    }
+}
+
+#[test]
+fn test_parse_theme() {
+    let theme = Theme::parse(
+        "# comment\n\nstyle.error = bold bright-red on #202020\n  char.vbar=|\nstyle.border = dim curly-underline\n",
+    )
+    .unwrap();
+    assert_eq!(
+        theme.error,
+        Style::new()
+            .bold()
+            .fg(Color::BrightRed)
+            .bg(Color::Rgb(32, 32, 32))
+    );
+    assert_eq!(theme.vbar, '|');
+    assert_eq!(
+        theme.border,
+        Style::new().dim().underline_kind(Underline::Curly)
+    );
+    assert_eq!(theme.warning, Theme::default().warning);
+
+    let text = "style.eror = red\nchar.hbar = --\nstyle.note = bold purple\nnonsense\n";
+    let errors = Theme::parse(text).unwrap_err();
+    let spans = errors
+        .iter()
+        .map(|error| &text[error.span.clone().unwrap()])
+        .collect::<Vec<_>>();
+    assert_eq!(spans, ["style.eror", "--", "bold purple", "nonsense"]);
+    assert_eq!(
+        errors[0].help.as_deref(),
+        Some("did you mean `style.error`?")
+    );
+}
+
+#[test]
+fn test_theme_env() {
+    let vars = |vars: &[(&str, &str)]| {
+        vars.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    };
+
+    let mut theme = Theme::default();
+    theme
+        .apply_vars(
+            "ARIADNE_",
+            vars(&[
+                ("PATH", "/bin"),
+                ("ARIADNE_STYLE_HELP", "italic cyan"),
+                ("ARIADNE_CHAR_RBOT", "+"),
+            ]),
+        )
+        .unwrap();
+    assert_eq!(theme.help, Style::new().italic().fg(Color::Cyan));
+    assert_eq!(theme.rbot, '+');
+
+    let errors = theme
+        .apply_vars("ARIADNE_", vars(&[("ARIADNE_STYLE_WARN", "yellow")]))
+        .unwrap_err();
+    assert_eq!(
+        errors[0].message,
+        "unknown theme variable `ARIADNE_STYLE_WARN`"
+    );
+    assert_eq!(
+        errors[0].help.as_deref(),
+        Some("did you mean `ARIADNE_STYLE_WARNING`?")
+    );
+    // Nothing is changed on errors
+    assert_eq!(theme.help, Style::new().italic().fg(Color::Cyan));
+}
@@ -47,6 +47,11 @@ pub enum Element {
     Inline {
         text: String,
     },
+    /// Inline text that links to `target`, backends without hyperlinks display only the text
+    Link {
+        text: String,
+        target: String,
+    },
 }
 
 impl Element {
@@ -75,6 +80,13 @@ impl Element {
         }
     }
 
+    pub fn link(text: impl ToString, target: impl ToString) -> Self {
+        Self::Link {
+            text: text.to_string(),
+            target: target.to_string(),
+        }
+    }
+
     pub fn styled(self, style: Style) -> Styled<Self> {
         Styled::new(self, style)
     }
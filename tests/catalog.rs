@@ -0,0 +1,73 @@
+use ariadne_next::{
+    Backend, Cache, DiagnosticDef, Explanation, Linear, PlainText, Report, ReportKind,
+};
+
+static E0412: DiagnosticDef = DiagnosticDef::new(
+    "E0412",
+    ReportKind::Error,
+    "cannot find type `{name}` in this scope",
+)
+.with_explanation(
+    "\
+A used type name is not in scope.
+
+Erroneous code examples:
+
+```compile_fail,E0412
+impl Something {} // error: type name `Something` is not in scope
+
+// or:
+
+trait Foo {
+\tfn bar(N); // error: type name `N` is not in scope
+}
+```
+
+# Fix
+
+Please verify that the name wasn't misspelled and ensure that it was imported.
+",
+);
+
+#[test]
+fn explanation_plaintext() {
+    let mut backend = PlainText(Vec::new());
+    E0412.explain().unwrap().write(&mut backend).unwrap();
+    let result = String::from_utf8(backend.0).unwrap();
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn explanation_linear() {
+    let mut backend = Linear(Vec::new());
+    E0412.explain().unwrap().write(&mut backend).unwrap();
+    let result = String::from_utf8(backend.0).unwrap();
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+/// Implemented without `write_explanation`
+struct Counter(usize);
+
+impl Backend for Counter {
+    type Error = ();
+
+    fn write<SourceId>(
+        &mut self,
+        _: &Report<SourceId>,
+        _: &mut impl Cache<SourceId>,
+    ) -> Result<(), Self::Error> {
+        self.0 += 1;
+        Ok(())
+    }
+}
+
+#[test]
+fn default_explanation() {
+    let mut backend = Counter(0);
+    Explanation::new("E0001", "Some explanation.")
+        .write(&mut backend)
+        .unwrap();
+    assert_eq!(backend.0, 0);
+}
@@ -0,0 +1,85 @@
+use ariadne_next::{
+    Cache, ColorDepth, DiagnosticDef, FileCache, Label, PlainText, ReportKind, SourceView, Stream,
+};
+use std::path::PathBuf;
+
+const SOURCE: &str = "struct Report {\n    view: Option<SourceView<Lab>>,\n}\n";
+
+static E0412: DiagnosticDef = DiagnosticDef::new(
+    "E0412",
+    ReportKind::Error,
+    "cannot find type `{name}` in this scope",
+)
+.with_url("https://doc.rust-lang.org/error_codes/E0412.html");
+
+/// Links sources to an editor
+struct EditorCache(Vec<(&'static str, &'static str)>);
+
+impl Cache<&'static str> for EditorCache {
+    type Error = ();
+    type DisplayedId<'a> = &'a str;
+
+    fn fetch(&mut self, id: &&'static str) -> Result<&str, Self::Error> {
+        self.0.fetch(id)
+    }
+
+    fn display_id<'a>(&self, id: &'a &'static str) -> Option<Self::DisplayedId<'a>> {
+        Some(*id)
+    }
+
+    fn link(&self, id: &&'static str, line: usize, col: usize) -> Option<String> {
+        Some(format!("vscode://file/project/{id}:{line}:{col}"))
+    }
+}
+
+fn render(hyperlinks: bool) -> String {
+    let report = E0412.report(&[("name", &"Lab")]).unwrap().with_view(
+        SourceView::new("src/lib.rs", 44)
+            .with_label(Label::new(44..47).with_message("not found in this scope")),
+    );
+
+    let mut backend = Stream::new(Vec::new(), ColorDepth::TrueColor).with_hyperlinks(hyperlinks);
+    report
+        .write(&mut backend, &mut EditorCache(vec![("src/lib.rs", SOURCE)]))
+        .unwrap();
+    String::from_utf8(backend.into_inner()).unwrap()
+}
+
+#[test]
+fn hyperlinks() {
+    let result = render(true);
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn disabled_hyperlinks() {
+    let mut backend = PlainText(Vec::new());
+    E0412
+        .report(&[("name", &"Lab")])
+        .unwrap()
+        .with_view(SourceView::new("src/lib.rs", 44).with_label(Label::new(44..47)))
+        .write(&mut backend, &mut EditorCache(vec![("src/lib.rs", SOURCE)]))
+        .unwrap();
+    let plaintext = String::from_utf8(backend.0).unwrap();
+
+    assert!(!render(false).contains("\x1B]8;;"));
+    assert!(plaintext.starts_with("[E0412] Error: cannot find type `Lab` in this scope\n"));
+    assert!(plaintext.contains("[src/lib.rs:2:29]"));
+}
+
+#[test]
+fn file_links() {
+    let cache = FileCache::default();
+    let path = PathBuf::from("/src/my file.rs");
+    assert_eq!(
+        cache.link(&path, 2, 29).unwrap(),
+        "file:///src/my%20file.rs"
+    );
+
+    let cache = FileCache::default().with_link_format("vscode://file{path}:{line}:{col}");
+    assert_eq!(
+        cache.link(&path, 2, 29).unwrap(),
+        "vscode://file/src/my%20file.rs:2:29"
+    );
+}
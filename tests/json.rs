@@ -0,0 +1,114 @@
+use ariadne_next::{Catalog, DiagnosticDef, Json, Label, Rendered, Report, ReportKind, SourceView};
+
+const SOURCE: &str =
+    "struct Report {\n    view: Option<SourceView<Lab>>,\n}\n\nfn größe() {\n    let x = 1;\n}\n";
+
+static E0412: DiagnosticDef = DiagnosticDef::new(
+    "E0412",
+    ReportKind::Error,
+    "cannot find type `{name}` in this scope",
+)
+.with_explanation("A used type name is not in scope.");
+
+fn reports() -> Vec<Report<'static, &'static str>> {
+    vec![
+        Report::new(ReportKind::Error)
+            .with_code("E0412")
+            .with_message("cannot find type `Lab` in this scope")
+            .with_view(
+                SourceView::new("src/lib.rs", 44)
+                    .with_label(Label::new(44..47).with_message("not found in this scope"))
+                    .with_label(Label::new(0..14).with_message("in this struct")),
+            )
+            .with_comment(ReportKind::Help, "you might be missing a type parameter"),
+        Report::new(ReportKind::Warning)
+            .with_message("function `größe` is never used")
+            .with_view(
+                SourceView::new("src/lib.rs", 57)
+                    .with_label(Label::new(57..83).with_message("multiple lines")),
+            ),
+        Report::new(ReportKind::Note)
+            .with_code("unexplained")
+            .with_message("location outside of the labels")
+            .with_view(
+                SourceView::new("src/lib.rs", 0)
+                    .with_label(Label::new(20..24))
+                    .with_label(Label::new(26..32)),
+            ),
+    ]
+}
+
+fn write(rendered: Rendered) -> String {
+    let catalog = Catalog::new().with_def(E0412);
+    let mut backend = Json::new(Vec::new())
+        .with_rendered(rendered)
+        .with_catalog(&catalog);
+    for report in reports() {
+        report
+            .write(&mut backend, &mut vec![("src/lib.rs", SOURCE)])
+            .unwrap();
+    }
+    String::from_utf8(backend.0).unwrap()
+}
+
+#[test]
+fn json() {
+    let result = write(Rendered::None);
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn rustc_fields() {
+    let result = write(Rendered::PlainText);
+    let diagnostics: Vec<serde_json::Value> = result
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(diagnostics.len(), 3);
+
+    let error = &diagnostics[0];
+    assert_eq!(error["level"], "error");
+    assert_eq!(error["code"]["code"], "E0412");
+    assert_eq!(
+        error["code"]["explanation"],
+        "A used type name is not in scope."
+    );
+    assert_eq!(error["children"][0]["level"], "help");
+    assert!(error["rendered"]
+        .as_str()
+        .unwrap()
+        .starts_with("[E0412] Error: cannot find type `Lab` in this scope\n"));
+
+    let span = &error["spans"][0];
+    assert_eq!(span["is_primary"], true);
+    assert_eq!(span["label"], "not found in this scope");
+    assert_eq!(
+        (&span["line_start"], &span["column_start"]),
+        (&2.into(), &29.into())
+    );
+    assert_eq!(
+        (&span["line_end"], &span["column_end"]),
+        (&2.into(), &32.into())
+    );
+    assert_eq!(error["spans"][1]["is_primary"], false);
+
+    // Columns count characters, not bytes
+    let span = &diagnostics[1]["spans"][0];
+    assert_eq!(
+        (&span["line_start"], &span["column_start"]),
+        (&5.into(), &4.into())
+    );
+    assert_eq!(
+        (&span["line_end"], &span["column_end"]),
+        (&6.into(), &15.into())
+    );
+    assert_eq!(span["text"][0]["highlight_start"], 4);
+    assert_eq!(span["text"][1]["highlight_end"], 15);
+
+    // The first label is primary if none contains the location
+    let note = &diagnostics[2];
+    assert_eq!(note["code"]["explanation"], serde_json::Value::Null);
+    assert_eq!(note["spans"][0]["is_primary"], true);
+    assert_eq!(note["spans"][1]["is_primary"], false);
+}
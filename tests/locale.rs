@@ -0,0 +1,71 @@
+use ariadne_next::{Label, Linear, Locale, PlainText, Report, ReportKind, SourceView};
+use std::borrow::Cow;
+
+const SOURCE: &str = "let 値 = \"文字列\";\nlet x = 値 + 1;\n";
+
+struct Japanese;
+
+impl Locale for Japanese {
+    fn kind_name(&self, kind: ReportKind) -> Cow<'static, str> {
+        match kind {
+            ReportKind::Error => "エラー".into(),
+            ReportKind::Warning => "警告".into(),
+            ReportKind::Help => "ヘルプ".into(),
+            ReportKind::Note => "注記".into(),
+            ReportKind::Custom(name, _) => name.into(),
+        }
+    }
+
+    // Japanese has no plural forms
+    fn kind_count(&self, kind: ReportKind, count: usize) -> String {
+        format!("{}{count}件", self.kind_name(kind))
+    }
+
+    fn empty_label(&self) -> Cow<'static, str> {
+        "<空のラベル>".into()
+    }
+
+    fn linear_source_line(&self, line: usize, text: &str) -> String {
+        format!("{line}行目: {text}")
+    }
+}
+
+fn report() -> Report<'static, &'static str> {
+    Report::new(ReportKind::Error)
+        .with_code(308)
+        .with_message("型が一致しません")
+        .with_view(
+            SourceView::new("main.rs", 31)
+                .with_label(Label::new(4..7).with_message("文字列型の値"))
+                .with_label(Label::new(31..34)),
+        )
+        .with_comment(ReportKind::Help, "値を数値に変換してください")
+        .with_locale(&Japanese)
+}
+
+#[test]
+fn translated_plaintext() {
+    let mut backend = PlainText(Vec::new());
+    report()
+        .write(&mut backend, &mut vec![("main.rs", SOURCE)])
+        .unwrap();
+    let result = String::from_utf8(backend.0).unwrap();
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn translated_linear() {
+    let mut backend = Linear(Vec::new());
+    report()
+        .write(&mut backend, &mut vec![("main.rs", SOURCE)])
+        .unwrap();
+    let result = String::from_utf8(backend.0).unwrap();
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn kind_counts() {
+    assert_eq!(Japanese.kind_count(ReportKind::Warning, 2), "警告2件");
+}
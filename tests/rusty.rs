@@ -1,4 +1,7 @@
-use ariadne_next::{Ansi, Label, PlainText, Report, ReportKind, SourceView};
+use ariadne_next::{
+    Ansi, Backend, Catalog, DiagnosticDef, Emitter, Label, Linear, PlainText, Report, ReportKind,
+    SourceView,
+};
 
 // Goal:
 // [E0412] Error: cannot find type `Lab` in this scope
@@ -29,7 +32,25 @@ fn cache() -> Vec<(&'static str, &'static str)> {
     vec![("src/lib.rs", include_str!("./test.rs.txt"))]
 }
 
-fn reports() -> [Report<'static, &'static str>; 4] {
+fn catalog() -> Catalog {
+    Catalog::from_iter([
+        DiagnosticDef::new(
+            "E0412",
+            ReportKind::Error,
+            "cannot find type `{name}` in this scope",
+        )
+        .with_explanation("A used type name is not in scope."),
+        DiagnosticDef::new(
+            "E0425",
+            ReportKind::Error,
+            "cannot find value `{name}` in this scope",
+        )
+        .with_explanation("An unresolved name was used."),
+    ])
+    .with_explain_command("rustc --explain")
+}
+
+fn reports() -> [Report<'static, &'static str>; 3] {
     // TODO Add separate Kind/Level for labels?
     // Kind::Add might control the characters "+++", color, ...
 
@@ -51,25 +72,36 @@ fn reports() -> [Report<'static, &'static str>; 4] {
                 Label::new(1386..1411).with_message("a field by that name exists in `Self`"),
                 Label::new(1518..1524),
             ])),
-        Report::new(ReportKind::Error)
-            .with_message("could not compile `ariadne-next` (lib) due to 2 previous errors"),
     ]
 }
 
-fn render_plainext() -> String {
-    let mut backend = PlainText(Vec::new());
+fn emit(backend: impl Backend<Error = std::io::Error>) {
+    let catalog = catalog();
+    let mut emitter = Emitter::new(backend, cache())
+        .with_catalog(&catalog)
+        .with_summary_subject("could not compile `ariadne-next` (lib)");
     for report in reports() {
-        report.write(&mut backend, &mut cache()).unwrap();
+        emitter.emit(report).unwrap();
     }
-    String::from_utf8(backend.0).unwrap()
+    emitter.finish().unwrap();
+}
+
+fn render_plainext() -> String {
+    let mut output = Vec::new();
+    emit(PlainText(&mut output));
+    String::from_utf8(output).unwrap()
 }
 
 fn render_ansi() -> String {
-    let mut backend = Ansi(Vec::new());
-    for report in reports() {
-        report.write(&mut backend, &mut cache()).unwrap();
-    }
-    String::from_utf8(backend.0).unwrap()
+    let mut output = Vec::new();
+    emit(Ansi(&mut output));
+    String::from_utf8(output).unwrap()
+}
+
+fn render_linear() -> String {
+    let mut output = Vec::new();
+    emit(Linear(&mut output));
+    String::from_utf8(output).unwrap()
 }
 
 #[test]
@@ -86,6 +118,13 @@ fn plaintext() {
     insta::assert_snapshot!(result);
 }
 
+#[test]
+fn linear() {
+    let result = render_linear();
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
 #[test]
 fn stripped_ansi() {
     assert_eq!(
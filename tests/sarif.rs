@@ -0,0 +1,136 @@
+use ariadne_next::{
+    Backend, Catalog, DiagnosticDef, Emitter, Label, Report, ReportKind, Sarif, SourceView,
+};
+
+const SOURCE: &str = "struct Report {\n    view: Option<SourceView<Lab>>,\n}\n\nfn größe() {}\n";
+
+static E0412: DiagnosticDef = DiagnosticDef::new(
+    "E0412",
+    ReportKind::Error,
+    "cannot find type `{name}` in this scope",
+)
+.with_explanation("A used type name is not in scope.")
+.with_url("https://doc.rust-lang.org/error_codes/E0412.html");
+
+fn write() -> serde_json::Value {
+    let reports = [
+        E0412
+            .report(&[("name", &"Lab")])
+            .unwrap()
+            .with_view(
+                SourceView::new("src/my lib.rs", 44)
+                    .with_label(Label::new(44..47).with_message("not found in this scope"))
+                    .with_label(Label::new(0..14).with_message("in this struct")),
+            )
+            .with_comment(ReportKind::Help, "you might be missing a type parameter"),
+        Report::new(ReportKind::Warning)
+            .with_code("dead_code")
+            .with_message("function `größe` is never used")
+            .with_view(SourceView::new("src/my lib.rs", 57).with_label(Label::new(57..64))),
+        Report::new(ReportKind::Note).with_message("no location"),
+        Report::new(ReportKind::Note)
+            .with_message("location outside of the labels")
+            .with_view(
+                SourceView::new("src/my lib.rs", 0)
+                    .with_label(Label::new(20..24))
+                    .with_label(Label::new(26..32)),
+            ),
+    ];
+
+    let catalog = Catalog::new().with_def(E0412);
+    let mut backend = Sarif::new(Vec::new(), "ariadne-next")
+        .with_version("0.1.0")
+        .with_information_uri("https://github.com/ariadne-next")
+        .with_catalog(&catalog);
+    for report in reports {
+        report
+            .write(&mut backend, &mut vec![("src/my lib.rs", SOURCE)])
+            .unwrap();
+    }
+    assert!(backend.0.is_empty());
+    backend.finish().unwrap();
+
+    serde_json::from_slice(&backend.0).unwrap()
+}
+
+#[test]
+fn sarif() {
+    let result = serde_json::to_string_pretty(&write()).unwrap();
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn sarif_fields() {
+    let log = write();
+    assert_eq!(log["version"], "2.1.0");
+
+    let run = &log["runs"][0];
+    assert_eq!(run["tool"]["driver"]["name"], "ariadne-next");
+    let rules = &run["tool"]["driver"]["rules"];
+    assert_eq!(rules[0]["id"], "E0412");
+    assert_eq!(
+        rules[0]["help"]["text"],
+        "A used type name is not in scope."
+    );
+    assert_eq!(rules[1]["id"], "dead_code");
+
+    let result = &run["results"][0];
+    assert_eq!(
+        (&result["ruleIndex"], &result["level"]),
+        (&0.into(), &"error".into())
+    );
+    let location = &result["locations"][0]["physicalLocation"];
+    assert_eq!(location["artifactLocation"]["uri"], "src/my%20lib.rs");
+    assert_eq!(location["region"]["startLine"], 2);
+    assert_eq!(location["region"]["startColumn"], 29);
+    assert_eq!(location["region"]["endColumn"], 32);
+    assert_eq!(
+        result["relatedLocations"][0]["message"]["text"],
+        "in this struct"
+    );
+
+    // Columns count characters, not bytes
+    let region = &run["results"][1]["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(
+        (&region["startColumn"], &region["endColumn"]),
+        (&4.into(), &9.into())
+    );
+
+    assert_eq!(run["results"][2]["level"], "note");
+    assert_eq!(run["results"][2]["locations"], serde_json::json!([]));
+
+    // The first label is the location if none contains the location of the view
+    let result = &run["results"][3];
+    let region = &result["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(region["byteOffset"], 20);
+    assert_eq!(result["relatedLocations"][0]["id"], 0);
+    assert_eq!(
+        result["relatedLocations"][0]["physicalLocation"]["region"]["byteOffset"],
+        26
+    );
+}
+
+#[test]
+fn sarif_emitter() {
+    let catalog = Catalog::new().with_def(E0412);
+    let backend = Sarif::new(Vec::new(), "ariadne-next").with_catalog(&catalog);
+    let mut emitter = Emitter::new(backend, vec![("src/my lib.rs", SOURCE)]).with_summary(false);
+    let report = E0412
+        .report(&[("name", &"Lab")])
+        .unwrap()
+        .with_view(SourceView::new("src/my lib.rs", 44).with_label(Label::new(44..47)));
+    emitter.emit(report).unwrap();
+    emitter.finish().unwrap();
+
+    // Finishing the emitter writes the log of the backend
+    let (backend, _) = emitter.into_inner();
+    let log: serde_json::Value = serde_json::from_slice(&backend.0).unwrap();
+    let run = &log["runs"][0];
+    assert_eq!(run["results"].as_array().unwrap().len(), 1);
+    assert_eq!(run["results"][0]["ruleId"], "E0412");
+    assert_eq!(
+        run["tool"]["driver"]["rules"][0]["help"]["text"],
+        "A used type name is not in scope."
+    );
+}
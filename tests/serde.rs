@@ -0,0 +1,95 @@
+#![cfg(feature = "serde")]
+
+use ariadne_next::{
+    Color, ColorGenerator, Label, PlainText, Report, ReportKind, SourceView, Style, StyleExt,
+    Styled, Underline,
+};
+
+const SOURCE: &str = "struct Report {\n    view: Option<SourceView<Lab>>,\n}\n";
+
+fn report() -> Report<'static, &'static str> {
+    Report::new(ReportKind::Error)
+        .with_code("E0412")
+        .with_url("https://doc.rust-lang.org/error_codes/E0412.html")
+        .with_message(["cannot find type ".into(), "`Lab`".bold()])
+        .with_view(
+            SourceView::new("src/lib.rs", 44)
+                .with_label(Label::new(44..47).with_message("not found in this scope"))
+                .with_label(Label::new(20..24).with_color(Color::Rgb(255, 136, 0)))
+                .with_color_generator(ColorGenerator::default()),
+        )
+        .with_comment(
+            ReportKind::Custom(
+                "Hint",
+                Style::new()
+                    .fg(Color::BrightCyan)
+                    .italic()
+                    .underline_kind(Underline::Curly),
+            ),
+            "you might be missing a type parameter",
+        )
+}
+
+fn render(report: &Report<&str>) -> String {
+    let mut backend = PlainText(Vec::new());
+    report
+        .write(&mut backend, &mut vec![("src/lib.rs", SOURCE)])
+        .unwrap();
+    String::from_utf8(backend.0).unwrap()
+}
+
+#[test]
+fn serialized_report() {
+    let json = serde_json::to_string_pretty(&report()).unwrap();
+    println!("{json}");
+    insta::assert_snapshot!(json);
+}
+
+#[test]
+fn roundtrip() {
+    let report = report();
+    let json = serde_json::to_string(&report).unwrap();
+    let deserialized: Report<&str> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(serde_json::to_string(&deserialized).unwrap(), json);
+    assert_eq!(render(&deserialized), render(&report));
+}
+
+#[test]
+fn optional_style_fields() {
+    let json = r#"{
+        "kind": "warning",
+        "code": null,
+        "url": null,
+        "message": [{ "text": "unused", "style": { "foreground": "Bright Red", "attributes": ["bold"] } }],
+        "views": [],
+        "comments": []
+    }"#;
+    let report: Report<String> = serde_json::from_str(json).unwrap();
+    assert!(
+        report
+            == Report::new(ReportKind::Warning).with_message([Styled::new(
+                "unused".into(),
+                Style::new().fg(Color::BrightRed).bold(),
+            )])
+    );
+
+    let Err(error) = serde_json::from_str::<Report<String>>(&json.replace("bold", "loud")) else {
+        panic!("unknown attributes are rejected");
+    };
+    let error = error.to_string();
+    assert!(error.starts_with("unknown variant `loud`"), "{error}");
+}
+
+#[test]
+fn interned_custom_kinds() {
+    let json = r#"{ "custom": ["Hint", {}] }"#;
+    let name = |kind| match kind {
+        ReportKind::Custom(name, _) => name,
+        _ => panic!("custom kind"),
+    };
+    let first = name(serde_json::from_str(json).unwrap());
+    let second = name(serde_json::from_str(json).unwrap());
+    assert_eq!(first, "Hint");
+    assert!(std::ptr::eq(first, second));
+}
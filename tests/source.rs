@@ -0,0 +1,206 @@
+use ariadne_next::{
+    Ansi, Color, ColorGenerator, Config, Highlighter, Label, LabelHighlight, PlainText, Report,
+    ReportKind, SourceView, Style, StyleExt, Styled, StyledStr, Underline,
+};
+
+fn render_plaintext(report: Report<'_, &'static str>, source: &'static str) -> String {
+    let mut backend = PlainText(Vec::new());
+    report
+        .write(&mut backend, &mut vec![("main.rs", source)])
+        .unwrap();
+    String::from_utf8(backend.0).unwrap()
+}
+
+fn render_ansi(report: Report<'_, &'static str>, source: &'static str) -> String {
+    let mut backend = Ansi(Vec::new());
+    report
+        .write(&mut backend, &mut vec![("main.rs", source)])
+        .unwrap();
+    String::from_utf8(backend.0).unwrap()
+}
+
+#[test]
+fn invisible_chars() {
+    let source = "let access_level = \"user\";\nif access_level != \"user\u{202e} \u{2066}// Check if admin\u{2069} \u{2066}\" {\n\tprintln!(\"\x1b[31mYou are an admin.\");\n}\n";
+
+    let result = render_plaintext(
+        Report::new(ReportKind::Warning)
+            .with_message("suspicious string literal")
+            .with_config(Config::new().with_invisible_chars_note(true))
+            .with_view(SourceView::new("main.rs", 46).with_labels([
+                Label::new(46..83).with_message("contains bidi control characters"),
+                Label::new(97..102),
+            ])),
+        source,
+    );
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn visible_whitespace() {
+    let source = "fn main() {\n\tlet a = 1;  \n    let b =\u{a0}2;\n}\n";
+
+    let result = render_plaintext(
+        Report::new(ReportKind::Warning)
+            .with_message("inconsistent whitespace")
+            .with_config(Config::new().with_visible_whitespace(true))
+            .with_view(
+                SourceView::new("main.rs", 12).with_labels([
+                    Label::new(12..13).with_message("indented with a tab"),
+                    Label::new(23..25).with_message("trailing whitespace"),
+                    Label::new(26..30)
+                        .with_message("indented with spaces")
+                        .with_visible_whitespace(false),
+                    Label::new(37..39).with_message("non-breaking space"),
+                ]),
+            ),
+        source,
+    );
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+/// Highlights keywords and line comments
+struct RustHighlighter;
+
+impl Highlighter<&str> for RustHighlighter {
+    fn highlight<'a>(&self, _: &&str, line: &'a str) -> Vec<StyledStr<'a>> {
+        let (code, comment) = line.split_at(line.find("//").unwrap_or(line.len()));
+
+        let mut parts = vec![];
+        for word in code.split_inclusive(' ') {
+            let style = match word.trim_end() {
+                "fn" | "let" | "mut" => Style::new().fg(Color::Magenta).bold(),
+                _ => Style::default(),
+            };
+            parts.push(Styled::new(word.into(), style));
+        }
+        parts.push(Styled::new(comment.into(), Style::new().fg(Color::Black)));
+        parts
+    }
+}
+
+#[test]
+fn highlighting() {
+    let source = "fn main() {\n    let mut a = 1; // one\n    a += \"2\";\n}\n";
+    let report = || {
+        Report::new(ReportKind::Error)
+            .with_message("cannot add `&str` to `{integer}`")
+            .with_highlighter(&RustHighlighter)
+            .with_config(Config::new().with_visible_whitespace(true))
+            .with_view(
+                SourceView::new("main.rs", 40).with_labels([
+                    Label::new(16..25).with_color(Color::Cyan),
+                    Label::new(47..50)
+                        .with_message("no implementation for `{integer} += &str`")
+                        .with_color(Color::Red),
+                ]),
+            )
+    };
+
+    let result = render_ansi(report(), source);
+    println!("{result}");
+    insta::assert_snapshot!(result);
+
+    assert_eq!(
+        render_plaintext(report(), source),
+        strip_ansi_escapes::strip_str(result)
+    );
+}
+
+#[test]
+fn label_highlight() {
+    let source = "fn main() {\n    let a: u32 = \"1\";\n}\n";
+    let report = |label_highlight| {
+        Report::new(ReportKind::Error)
+            .with_message("mismatched types")
+            .with_highlighter(&RustHighlighter)
+            .with_config(Config::new().with_label_highlight(label_highlight))
+            .with_view(
+                SourceView::new("main.rs", 29).with_labels([
+                    Label::new(23..26)
+                        .with_message("expected due to this")
+                        .with_color(Color::Blue),
+                    Label::new(29..32)
+                        .with_message("expected `u32`, found `&str`")
+                        .with_color(Color::Red),
+                ]),
+            )
+    };
+
+    let result = [
+        LabelHighlight::Foreground,
+        LabelHighlight::Background,
+        LabelHighlight::Underline(Underline::Curly),
+    ]
+    .map(|label_highlight| render_ansi(report(label_highlight), source))
+    .join("");
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn generated_label_colors() {
+    let source = "fn add(a: u32, b: u32, c: u32) -> u32 {\n    a + b + c\n}\n";
+    let report = Report::new(ReportKind::Note)
+        .with_message("parameters are used here")
+        .with_config(Config::new().with_label_highlight(LabelHighlight::Foreground))
+        .with_view(
+            SourceView::new("main.rs", 44)
+                .with_color_generator(ColorGenerator::from_seed(3))
+                .with_labels([
+                    Label::new(44..45),
+                    // Explicit colors are kept and not generated for other labels
+                    Label::new(48..49).with_color(Color::Rgb(240, 228, 66)),
+                    Label::new(52..53),
+                ]),
+        );
+
+    let result = render_ansi(report, source);
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn generated_colors_in_messages() {
+    let mut view = SourceView::new("main.rs", 44).with_color_generator(ColorGenerator::new());
+    let mut colors = vec![];
+    for span in [44..45, 48..49] {
+        let color = view.next_label_color().unwrap();
+        view.add_label(Label::new(span).with_message(["of type ".into(), "u32".fg(color)]));
+        colors.push(color);
+    }
+    view.add_label(Label::new(52..53).with_color(Color::Red));
+
+    assert_eq!(view.label_colors(), [colors[0], colors[1], Color::Red]);
+    assert_ne!(colors[0], colors[1]);
+    assert_eq!(SourceView::new("main.rs", 0).next_label_color(), None);
+}
+
+#[test]
+fn missing_source() {
+    let mut backend = PlainText(Vec::new());
+    Report::new(ReportKind::Error)
+        .with_message("source is gone")
+        .with_view(
+            SourceView::new("deleted.rs", 3).with_label(Label::new(3..5).with_message("was here")),
+        )
+        .write(&mut backend, &mut vec![("main.rs", "fn main() {}\n")])
+        .unwrap();
+    let result = String::from_utf8(backend.0).unwrap();
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn ansi_without_term() {
+    // The backend was chosen explicitly, so colors are written for any terminal
+    std::env::set_var("TERM", "");
+    let result = render_ansi(
+        Report::new(ReportKind::Error).with_message("mismatched types"),
+        "",
+    );
+    println!("{result}");
+    assert!(result.starts_with("\x1B[1;31mError"), "{result:?}");
+}
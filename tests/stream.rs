@@ -0,0 +1,93 @@
+use ariadne_next::{
+    ColorChoice, ColorDepth, Label, PlainText, Report, ReportKind, SourceView, Stream,
+};
+use std::io;
+
+const SOURCE: &str = "fn main() {\n    let x: u32 = \"1\";\n}\n";
+
+/// Remembers how much output was written at each flush
+#[derive(Default)]
+struct FlushRecorder {
+    output: Vec<u8>,
+    flushes: Vec<usize>,
+}
+
+impl io::Write for FlushRecorder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.output.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flushes.push(self.output.len());
+        Ok(())
+    }
+}
+
+fn reports() -> [Report<'static, &'static str>; 2] {
+    [
+        Report::new(ReportKind::Error)
+            .with_message("mismatched types")
+            .with_view(
+                SourceView::new("main.rs", 29)
+                    .with_label(Label::new(29..32).with_message("expected `u32`")),
+            ),
+        Report::new(ReportKind::Warning).with_message("unused variable `x`"),
+    ]
+}
+
+fn write(color_depth: ColorDepth) -> FlushRecorder {
+    let mut backend = Stream::new(FlushRecorder::default(), color_depth);
+    for report in reports() {
+        report
+            .write(&mut backend, &mut vec![("main.rs", SOURCE)])
+            .unwrap();
+    }
+    backend.into_inner()
+}
+
+#[test]
+fn stream_ansi() {
+    let recorder = write(ColorDepth::Ansi16);
+    let result = String::from_utf8(recorder.output).unwrap();
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn stream_plaintext() {
+    let recorder = write(ColorDepth::None);
+
+    let mut plaintext = PlainText(Vec::new());
+    for report in reports() {
+        report
+            .write(&mut plaintext, &mut vec![("main.rs", SOURCE)])
+            .unwrap();
+    }
+    assert_eq!(recorder.output, plaintext.0);
+}
+
+#[test]
+fn stream_flushes_each_report() {
+    let recorder = write(ColorDepth::Ansi16);
+    let output = String::from_utf8(recorder.output).unwrap();
+
+    // Flushed once after each complete report
+    let first_end = output.find("\x1B[1;33mWarning").unwrap();
+    assert_eq!(recorder.flushes, [first_end, output.len()]);
+}
+
+#[test]
+fn stream_color_choice() {
+    // Files are not terminals
+    let name = format!(
+        "ariadne-next-{}-stream-color-choice.txt",
+        std::process::id()
+    );
+    let path = std::env::temp_dir().join(name);
+    let file = || std::fs::File::create(&path).unwrap();
+
+    assert!(!Stream::from_color_choice(file(), ColorChoice::Never).is_ansi());
+    assert!(Stream::from_color_choice(file(), ColorChoice::Always).is_ansi());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,44 @@
+use ariadne_next::{Config, Label, PlainText, Report, ReportKind, SourceView, Theme};
+
+const THEME: &str = "\
+# High contrast theme
+style.error = bold bright-red on black
+style.warnign = yellow
+style.border = dim
+char.vbar = ||
+char.ltop = +
+style.note = bold mauve
+";
+
+#[test]
+fn theme_errors() {
+    let errors = Theme::parse(THEME).unwrap_err();
+
+    let mut backend = PlainText(Vec::new());
+    for error in &errors {
+        error
+            .report("theme.conf")
+            .write(&mut backend, &mut vec![("theme.conf", THEME)])
+            .unwrap();
+    }
+    let result = String::from_utf8(backend.0).unwrap();
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}
+
+#[test]
+fn themed_report() {
+    let theme =
+        Theme::parse("char.hbar = -\nchar.vbar = |\nchar.ltop = ,\nchar.rbot = '\n").unwrap();
+
+    let mut backend = PlainText(Vec::new());
+    Report::new(ReportKind::Warning)
+        .with_message("unused key")
+        .with_config(Config::new().with_theme(theme))
+        .with_view(SourceView::new("theme.conf", 22).with_label(Label::new(22..43)))
+        .write(&mut backend, &mut vec![("theme.conf", THEME)])
+        .unwrap();
+    let result = String::from_utf8(backend.0).unwrap();
+    println!("{result}");
+    insta::assert_snapshot!(result);
+}